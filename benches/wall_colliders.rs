@@ -0,0 +1,62 @@
+//! Compare the number of wall entities spawned per tile and per merged rectangle
+//!
+//! Run with `cargo bench --bench wall_colliders`.
+
+use std::{hint::black_box, time::Instant};
+
+use bevy_ecs_ldtk::ldtk::LdtkJson;
+
+// The game is a binary crate, the merging is included from its source
+#[allow(dead_code)]
+#[path = "../src/platformer/walls.rs"]
+mod walls;
+
+/// Number of merges timed for each level.
+const ITERATIONS: u32 = 1000;
+
+fn main() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/world.ldtk");
+    let content = std::fs::read_to_string(path).expect("LDtk project exists");
+    let project: LdtkJson = serde_json::from_str(&content).expect("LDtk project is valid");
+
+    println!(
+        "{:<12} {:>12} {:>12} {:>12}",
+        "level", "per tile", "merged", "merge time"
+    );
+
+    let (mut total_tiles, mut total_colliders) = (0, 0);
+    for level in &project.levels {
+        for layer in level.layer_instances.iter().flatten() {
+            let cells = walls::wall_cells(layer);
+            if cells.is_empty() {
+                continue;
+            }
+
+            let start = Instant::now();
+            for _ in 0..ITERATIONS {
+                black_box(walls::merge_cells(
+                    black_box(&cells),
+                    layer.c_wid,
+                    layer.c_hei,
+                ));
+            }
+            let merge_time = start.elapsed() / ITERATIONS;
+
+            let colliders = walls::merge_cells(&cells, layer.c_wid, layer.c_hei).len();
+            total_tiles += cells.len();
+            total_colliders += colliders;
+            println!(
+                "{:<12} {:>12} {:>12} {:>12?}",
+                level.identifier,
+                cells.len(),
+                colliders,
+                merge_time
+            );
+        }
+    }
+
+    println!(
+        "{:<12} {:>12} {:>12}",
+        "total", total_tiles, total_colliders
+    );
+}
@@ -2,24 +2,140 @@
 
 use bevy::prelude::*;
 
+use bevy_ecs_ldtk::prelude::{EntityIid, LevelIid};
+
 pub struct SfEventsPlugin;
 
 impl Plugin for SfEventsPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<JumpEvent>()
+            .add_message::<LandEvent>()
             .add_message::<DeathEvent>()
-            .add_message::<RespawnEvent>();
+            .add_message::<RespawnRequestEvent>()
+            .add_message::<RespawnEvent>()
+            .add_message::<SpawnedEvent>()
+            .add_message::<SpringEvent>()
+            .add_message::<LevelEnterEvent>()
+            .add_message::<CheckpointEvent>()
+            .add_message::<GenesChangedEvent>()
+            .add_message::<ButtonEvent>()
+            .add_message::<DoorEvent>();
     }
 }
 
 /// Event sent when the player jumps.
 #[derive(Message, Debug)]
-pub struct JumpEvent;
+pub struct JumpEvent {
+    pub player: Entity,
+    pub position: Vec2,
+    pub level: Option<LevelIid>,
+    /// Jumps left before this one.
+    pub remaining_jumps: u32,
+}
+
+/// Event sent when the player touches the ground.
+#[derive(Message, Debug)]
+pub struct LandEvent {
+    pub player: Entity,
+    pub position: Vec2,
+    pub level: Option<LevelIid>,
+    pub remaining_jumps: u32,
+}
+
+/// Why the player died.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    /// The player landed without any jump left.
+    OutOfJumps,
+    /// The player restarted from the last checkpoint.
+    Restart,
+}
 
 /// Event sent when the player dies
 #[derive(Message, Debug)]
-pub struct DeathEvent;
+pub struct DeathEvent {
+    pub player: Entity,
+    pub position: Vec2,
+    pub level: Option<LevelIid>,
+    pub cause: DeathCause,
+}
+
+/// Event sent when the player asks to respawn from the death menu
+#[derive(Message, Debug)]
+pub struct RespawnRequestEvent;
 
 /// Event sent when the player Respawn
 #[derive(Message, Debug)]
-pub struct RespawnEvent;
+pub struct RespawnEvent {
+    pub player: Entity,
+    pub position: Vec2,
+    pub level: Option<LevelIid>,
+}
+
+/// Event sent when the player can be controlled again after a respawn
+#[derive(Message, Debug)]
+pub struct SpawnedEvent {
+    pub player: Entity,
+    pub position: Vec2,
+    pub level: Option<LevelIid>,
+}
+
+/// Event sent when the player is launched by a spring
+#[derive(Message, Debug)]
+pub struct SpringEvent {
+    pub player: Entity,
+    pub position: Vec2,
+    pub level: Option<LevelIid>,
+}
+
+/// Event sent when the player enters a level
+#[derive(Message, Debug)]
+pub struct LevelEnterEvent {
+    pub player: Entity,
+    pub position: Vec2,
+    pub level: LevelIid,
+    /// Whether the player never was in this level before.
+    pub first_visit: bool,
+}
+
+/// Event sent when the player activates a checkpoint
+#[derive(Message, Debug)]
+pub struct CheckpointEvent {
+    pub checkpoint: Entity,
+    pub iid: EntityIid,
+    pub position: Vec2,
+    pub level: Option<LevelIid>,
+}
+
+/// Event sent when the genes of the player are applied
+#[derive(Message, Debug)]
+pub struct GenesChangedEvent {
+    pub player: Entity,
+    pub max_jumps: u32,
+}
+
+/// Event sent when a button is pressed or released, or a lever is toggled
+#[derive(Message, Debug)]
+pub struct ButtonEvent {
+    pub button: Entity,
+    pub iid: EntityIid,
+    pub pressed: bool,
+}
+
+/// State of a door.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DoorState {
+    #[default]
+    Closed,
+    Opening,
+    Open,
+    Closing,
+}
+
+/// Event sent when a door changes state
+#[derive(Message, Debug)]
+pub struct DoorEvent {
+    pub door: Entity,
+    pub iid: EntityIid,
+    pub state: DoorState,
+}
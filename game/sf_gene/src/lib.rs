@@ -3,7 +3,7 @@
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use bevy_common_assets::ron::RonAssetPlugin;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 pub struct SfGenePlugin;
 
@@ -22,7 +22,7 @@ pub struct GeneDatabase(pub Vec<Gene>);
 pub struct GeneDatabaseHandle(pub Handle<GeneDatabase>);
 
 /// Enum for the different Gene stats
-#[derive(Clone, Debug, Deserialize, Reflect)]
+#[derive(Clone, Debug, Deserialize, Serialize, Reflect)]
 pub enum GeneStat {
     JumpAmount(i32),    // Number of jumps before death
     JumpHeight(f32),    // Jump height in blocks (8 pixels)
@@ -37,7 +37,7 @@ impl Default for GeneStat {
 }
 
 /// Struct that describes a gene
-#[derive(Clone, Debug, Default, Deserialize, Reflect)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Reflect)]
 pub struct Gene {
     pub id: usize,
     pub name: String,
@@ -100,7 +100,7 @@ impl Gene {
 }
 
 /// Resource with all the genes of the character
-#[derive(Resource, Reflect, Debug, Default)]
+#[derive(Resource, Reflect, Debug, Default, Clone, Deserialize, Serialize)]
 #[reflect(Resource)]
 pub struct PlayerGenes {
     base: Gene,
@@ -0,0 +1,38 @@
+//! Gene station opened from an active checkpoint, the genes can be changed without dying
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use sf_gene::PlayerGenes;
+
+use crate::{menus::gene_editor::spawn_gene_editor, states::Menu, ui::widget};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::GeneStation), spawn_gene_station);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::GeneStation).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+fn spawn_gene_station(mut commands: Commands, player_genes: Res<PlayerGenes>) {
+    let root = commands
+        .spawn((
+            widget::ui_root("Gene Station"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::GeneStation),
+        ))
+        .id();
+
+    commands.spawn((widget::header("Gene Station"), ChildOf(root)));
+    spawn_gene_editor(&mut commands, root, &player_genes);
+
+    commands.spawn((ChildOf(root), widget::button("Continue", continue_game)));
+}
+
+fn continue_game(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
@@ -2,6 +2,8 @@
 
 mod credits;
 pub(crate) mod death_menu;
+mod gene_editor;
+mod gene_station;
 mod main_menu;
 mod pause;
 mod settings;
@@ -12,6 +14,8 @@ pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         credits::plugin,
         death_menu::plugin,
+        gene_editor::plugin,
+        gene_station::plugin,
         main_menu::plugin,
         settings::plugin,
         pause::plugin,
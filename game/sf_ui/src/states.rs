@@ -28,4 +28,6 @@ pub enum Menu {
     Settings,
     Pause,
     Death,
+    Map,
+    GeneStation,
 }
@@ -29,4 +29,9 @@ pub struct LevelAssets {
     pub world: Handle<LdtkProject>,
     #[asset(path = "audio/music/Going Up.ogg")]
     pub music: Handle<AudioSource>,
+    #[asset(path = "audio/sound_effects/door.wav")]
+    pub door_sound: Handle<AudioSource>,
+    /// No dedicated sample yet, the springs reuse the jump sound.
+    #[asset(path = "audio/sound_effects/jump.wav")]
+    pub spring_sound: Handle<AudioSource>,
 }
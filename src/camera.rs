@@ -3,6 +3,13 @@ use bevy::prelude::*;
 
 use bevy_modern_pixel_camera::prelude::*;
 
+use sf_ui::prelude::Screen;
+
+use crate::{
+    platformer::{level::CurrentLevel, transition::RoomTransition},
+    player::{death::PendingPlacement, physics::CharacterController},
+};
+
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(PixelCameraPlugin);
 
@@ -10,8 +17,18 @@ pub(super) fn plugin(app: &mut App) {
 
     // Spawn the main camera.
     app.add_systems(Startup, spawn_camera);
+
+    app.add_systems(
+        PostUpdate,
+        follow_player.before(TransformSystems::Propagate).run_if(
+            in_state(Screen::Gameplay)
+                .and(not(resource_exists::<RoomTransition>))
+                .and(not(resource_exists::<PendingPlacement>)),
+        ),
+    );
 }
 
+/// Size of the area shown by the camera, levels are at least this big.
 pub const LEVEL_WIDTH: f32 = 320.0;
 pub const LEVEL_HEIGHT: f32 = 180.0;
 
@@ -19,11 +36,37 @@ pub const LEVEL_HEIGHT: f32 = 180.0;
 #[reflect(Component)]
 pub struct MainCamera;
 
+/// Make the camera follow the player inside the bounds of the current level.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct CameraFollow {
+    /// Area around the center of the camera in which the player moves without moving the camera.
+    pub dead_zone: Vec2,
+    /// Distance the camera looks ahead in the facing direction of the player.
+    pub look_ahead: f32,
+    /// How fast the camera catches up with the player, higher is faster.
+    pub smoothing: f32,
+    /// Current look-ahead, eased toward the facing direction.
+    look_ahead_offset: f32,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            dead_zone: Vec2::new(32.0, 48.0),
+            look_ahead: 24.0,
+            smoothing: 6.0,
+            look_ahead_offset: 0.0,
+        }
+    }
+}
+
 fn spawn_camera(mut commands: Commands) {
     commands.spawn((
         Name::new("Camera"),
         Camera2d,
         MainCamera,
+        CameraFollow::default(),
         PixelViewport,
         WithUiScaling,
         PixelZoom::FitSize {
@@ -33,3 +76,60 @@ fn spawn_camera(mut commands: Commands) {
         Transform::from_xyz(LEVEL_WIDTH / 2.0, -LEVEL_HEIGHT / 2.0, 0.0),
     ));
 }
+
+fn follow_player(
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    player: Single<(&Transform, &Sprite), (With<CharacterController>, Without<MainCamera>)>,
+    camera: Single<(&mut Transform, &mut CameraFollow), With<MainCamera>>,
+) {
+    if current_level.iid.is_none() {
+        return;
+    }
+
+    let (player_transform, player_sprite) = *player;
+    let (mut camera_transform, mut follow) = camera.into_inner();
+
+    let facing = if player_sprite.flip_x { -1.0 } else { 1.0 };
+    let (look_ahead, smoothing) = (facing * follow.look_ahead, follow.smoothing);
+    follow
+        .look_ahead_offset
+        .smooth_nudge(&look_ahead, smoothing, time.delta_secs());
+
+    let target = player_transform.translation.truncate() + Vec2::X * follow.look_ahead_offset;
+    let mut position = camera_transform.translation.truncate();
+
+    if current_level.is_changed() {
+        // Cut to the new level when there is no transition
+        position = target;
+    } else {
+        // Only move by what the target is out of the dead zone
+        let distance = target - position;
+        let half_dead_zone = follow.dead_zone / 2.0;
+        let goal = position + distance - distance.clamp(-half_dead_zone, half_dead_zone);
+        position.smooth_nudge(&goal, smoothing, time.delta_secs());
+    }
+
+    let position = clamp_to_level(position, current_level.bounds);
+    camera_transform.translation = position.extend(camera_transform.translation.z);
+}
+
+/// Keep the view inside the level, centered on the axes where the level is smaller than the view.
+pub fn clamp_to_level(position: Vec2, bounds: Rect) -> Vec2 {
+    let half_view = Vec2::new(LEVEL_WIDTH, LEVEL_HEIGHT) / 2.0;
+    let min = bounds.min + half_view;
+    let max = bounds.max - half_view;
+
+    Vec2::new(
+        if min.x <= max.x {
+            position.x.clamp(min.x, max.x)
+        } else {
+            bounds.center().x
+        },
+        if min.y <= max.y {
+            position.y.clamp(min.y, max.y)
+        } else {
+            bounds.center().y
+        },
+    )
+}
@@ -12,6 +12,7 @@ use crate::{
     AppSystems, PausableSystems,
     audio::sound_effect,
     demo::{movement::MovementController, player::PlayerAssets},
+    utils::rng::RunRng,
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -77,6 +78,7 @@ fn update_animation_atlas(mut query: Query<(&PlayerAnimation, &mut Sprite)>) {
 fn trigger_step_sound_effect(
     mut commands: Commands,
     player_assets: Res<PlayerAssets>,
+    mut rng: ResMut<RunRng>,
     mut step_query: Query<&PlayerAnimation>,
 ) {
     for animation in &mut step_query {
@@ -84,8 +86,7 @@ fn trigger_step_sound_effect(
             && animation.changed()
             && (animation.frame == 2 || animation.frame == 5)
         {
-            let rng = &mut rand::rng();
-            let random_step = player_assets.steps.choose(rng).unwrap().clone();
+            let random_step = player_assets.steps.choose(&mut **rng).unwrap().clone();
             commands.spawn(sound_effect(random_step));
         }
     }
@@ -10,6 +10,8 @@ mod camera;
 mod dev_tools;
 mod platformer;
 mod player;
+#[cfg(not(target_family = "wasm"))]
+mod replay;
 mod screens;
 mod utils;
 
@@ -72,14 +74,17 @@ impl Plugin for AppPlugin {
             dev_tools::plugin,
             player::plugin,
             platformer::plugin,
+            #[cfg(not(target_family = "wasm"))]
+            replay::plugin,
             screens::plugin,
             utils::plugin,
         ));
 
         // Resources
         app.insert_resource(LdtkSettings {
+            // The neighbours are spawned by the level streaming
             level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation {
-                load_level_neighbors: true,
+                load_level_neighbors: false,
             },
             ..Default::default()
         });
@@ -98,6 +103,7 @@ impl Plugin for AppPlugin {
         // Set up the `Pause` state.
         app.init_state::<Pause>();
         app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
+        app.configure_sets(FixedUpdate, PausableSystems.run_if(in_state(Pause(false))));
     }
 }
 
@@ -130,6 +136,10 @@ enum Action {
     Left,
     Right,
     Jump,
+    // Interaction
+    Interact,
+    // Menus
+    Map,
 }
 
 impl Action {
@@ -0,0 +1,131 @@
+//! Relationship between the activators (buttons, logic gates, etc.) and the devices they activate
+
+use bevy::prelude::*;
+
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_ui::prelude::Screen;
+
+use crate::{
+    platformer::entities::logic::{LogicInputs, LogicOperation, evaluate_logic},
+    utils::entity_ref::{UnresolvedEntityRefs, resolve_entity_refs},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            resolve_entity_refs::<UnresolvedActivateByRef>,
+            evaluate_logic,
+            propagate_activation,
+        )
+            .chain()
+            .in_set(ActivationSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// System set in which the activation statuses are propagated from the activators to the devices.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ActivationSystems;
+
+/// Relationship component to represent the device is activated by
+#[derive(Component, Deref)]
+#[relationship(relationship_target = Activate)]
+#[require(ActivationStatus)]
+pub struct ActivatedBy(pub Entity);
+
+/// Relationship component to represent all device it activate
+#[derive(Component)]
+#[relationship_target(relationship = ActivatedBy)]
+pub struct Activate(Vec<Entity>);
+
+impl Activate {
+    pub fn entities(&self) -> &Vec<Entity> {
+        &self.0
+    }
+}
+
+/// Component to track the activation status.
+///
+/// On an activator it is the signal it emits, on a device the signal it receives.
+#[derive(Component, Debug, Default)]
+pub struct ActivationStatus(pub bool);
+
+/// Component to handle unresolved relationship with the activators of a device
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct UnresolvedActivateByRef {
+    refs: Vec<EntityIid>,
+    /// How the activators are combined when there are several of them.
+    operation: LogicOperation,
+}
+
+impl UnresolvedActivateByRef {
+    pub fn from_field(entity_instance: &EntityInstance) -> Self {
+        let mut refs: Vec<EntityIid> = entity_instance
+            .get_maybe_entity_ref_field("activater")
+            .expect("Expected entity to have activate entity ref field")
+            .iter()
+            .map(|entity_ref| EntityIid::new(entity_ref.entity_iid.clone()))
+            .collect();
+
+        // Devices with several inputs list them in an optional array field
+        if let Ok(activaters) = entity_instance.get_maybe_entity_refs_field("activaters") {
+            refs.extend(
+                activaters
+                    .iter()
+                    .flatten()
+                    .map(|entity_ref| EntityIid::new(entity_ref.entity_iid.clone())),
+            );
+        }
+
+        Self {
+            refs,
+            operation: LogicOperation::from_field(entity_instance),
+        }
+    }
+}
+
+impl UnresolvedEntityRefs for UnresolvedActivateByRef {
+    fn refs(&self) -> &[EntityIid] {
+        &self.refs
+    }
+
+    fn resolve(&self, commands: &mut Commands, entity: Entity, activaters: Vec<Entity>) {
+        if activaters.is_empty() {
+            return;
+        }
+
+        if activaters.len() == 1 && self.operation == LogicOperation::Or {
+            commands.entity(entity).insert(ActivatedBy(activaters[0]));
+        } else {
+            // The device combines its activators like a logic gate
+            commands
+                .entity(entity)
+                .insert((self.operation, LogicInputs(activaters)));
+        }
+    }
+}
+
+/// Send the status of the activators to the devices they activate.
+fn propagate_activation(
+    activators: Query<
+        (&ActivationStatus, &Activate),
+        (
+            Or<(Changed<ActivationStatus>, Changed<Activate>)>,
+            Without<ActivatedBy>,
+        ),
+    >,
+    mut devices: Query<&mut ActivationStatus, With<ActivatedBy>>,
+) {
+    for (status, activated_devices) in activators {
+        for device_entity in activated_devices.entities() {
+            let _ = devices.get_mut(*device_entity).map(|mut device_status| {
+                if device_status.0 != status.0 {
+                    device_status.0 = status.0;
+                }
+            });
+        }
+    }
+}
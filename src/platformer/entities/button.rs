@@ -3,9 +3,16 @@ use bevy::prelude::*;
 use avian2d::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 
+use sf_events::ButtonEvent;
 use sf_ui::prelude::Screen;
 
-use crate::GameLayer;
+use crate::{
+    GameLayer,
+    platformer::{
+        entities::activation::{ActivationStatus, ActivationSystems},
+        world_state::{PersistentState, WorldState},
+    },
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_ldtk_entity::<PushButtonBundle>("Button");
@@ -13,61 +20,51 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (
             process_button,
-            (
-                detect_button_press,
-                update_sprite_push_button,
-                update_activation_devices,
-            )
-                .chain(),
+            detect_button_press,
+            update_sprite_push_button,
+            update_button_status,
         )
+            .chain()
+            .before(ActivationSystems)
             .run_if(in_state(Screen::Gameplay)),
     );
 }
 
-/// Relationship component to represent the device is activated by
-#[derive(Component, Deref)]
-#[relationship(relationship_target = Activate)]
-#[require(ActivationStatus)]
-pub struct ActivatedBy(pub Entity);
-
-/// Relationship component to represent all device it activate
-#[derive(Component)]
-#[relationship_target(relationship = ActivatedBy)]
-pub struct Activate(Vec<Entity>);
-
-impl Activate {
-    pub fn entities(&self) -> &Vec<Entity> {
-        &self.0
-    }
-}
-
-/// Component to track the activation status
 #[derive(Component, Debug, Default)]
-pub struct ActivationStatus(pub bool);
+pub struct PushButton(pub bool);
 
-/// Component to handle unresolved relationship at ldtk startup
-#[derive(Component, Reflect, Debug, Default)]
+/// How a [`PushButton`] reacts when nothing is on it anymore.
+#[derive(Component, Reflect, Debug, Default, PartialEq, Eq)]
 #[reflect(Component)]
-pub struct UnresolvedActivateByRef(pub Option<EntityIid>);
-
-impl UnresolvedActivateByRef {
-    pub fn from_field(entity_instance: &EntityInstance) -> Self {
-        Self(
-            entity_instance
-                .get_maybe_entity_ref_field("activater")
-                .expect("Expected entity to have activate entity ref field")
-                .as_ref()
-                .map(|entity_ref| EntityIid::new(entity_ref.entity_iid.clone())),
-        )
-    }
+pub enum ButtonMode {
+    /// Released as soon as nothing is on it.
+    #[default]
+    Momentary,
+    /// Stays pressed once stepped on.
+    Latching,
 }
 
-#[derive(Component, Debug, Default)]
-pub struct PushButton(pub bool);
+impl ButtonMode {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let latching = entity_instance
+            .get_bool_field("latching")
+            .copied()
+            .unwrap_or(false);
+
+        if latching {
+            Self::Latching
+        } else {
+            Self::Momentary
+        }
+    }
+}
 
 #[derive(Bundle, LdtkEntity)]
 struct PushButtonBundle {
     button: PushButton,
+    #[with(ButtonMode::from_field)]
+    mode: ButtonMode,
+    status: ActivationStatus,
     #[sprite_sheet]
     sprite: Sprite,
 
@@ -84,6 +81,8 @@ impl Default for PushButtonBundle {
     fn default() -> Self {
         Self {
             button: PushButton(false),
+            mode: ButtonMode::Momentary,
+            status: ActivationStatus(false),
             sprite: Sprite::default(),
             body: RigidBody::Static,
             collider: Collider::rectangle(4.0, 2.0),
@@ -95,17 +94,51 @@ impl Default for PushButtonBundle {
     }
 }
 
-fn process_button(new_entity_instance: Query<&mut Transform, Added<PushButton>>) {
-    for mut transform in new_entity_instance {
+fn process_button(
+    world_state: Res<WorldState>,
+    new_entity_instance: Query<
+        (&EntityIid, &ButtonMode, &mut PushButton, &mut Transform),
+        Added<PushButton>,
+    >,
+) {
+    for (entity_iid, mode, mut button, mut transform) in new_entity_instance {
         // Small offset to fix delta
         transform.translation.y += 3.0;
+
+        // Latching buttons stay pressed when the level is reloaded
+        if *mode == ButtonMode::Latching && world_state.is_active(entity_iid) == Some(true) {
+            button.0 = true;
+        }
     }
 }
 
-fn detect_button_press(mut query: Query<(&CollidingEntities, &mut PushButton)>) {
-    for (colliding_entities, mut button) in &mut query {
+fn detect_button_press(
+    mut query: Query<(
+        Entity,
+        &EntityIid,
+        &CollidingEntities,
+        &ButtonMode,
+        &mut PushButton,
+    )>,
+    mut world_state: ResMut<WorldState>,
+    mut button_event: MessageWriter<ButtonEvent>,
+) {
+    for (entity, entity_iid, colliding_entities, mode, mut button) in &mut query {
+        if *mode == ButtonMode::Latching && button.0 {
+            continue;
+        }
+
         if colliding_entities.0.is_empty() == button.0 {
             button.0 = !button.0;
+            if *mode == ButtonMode::Latching {
+                world_state.set(entity_iid, PersistentState::Active(true));
+            }
+
+            button_event.write(ButtonEvent {
+                button: entity,
+                iid: entity_iid.clone(),
+                pressed: button.0,
+            });
         }
     }
 }
@@ -118,15 +151,8 @@ fn update_sprite_push_button(query: Query<(&mut Sprite, &PushButton), Changed<Pu
     }
 }
 
-fn update_activation_devices(
-    buttons: Query<(&PushButton, &Activate), Changed<PushButton>>,
-    mut devices: Query<&mut ActivationStatus, With<ActivatedBy>>,
-) {
-    for (button, activated_devices) in buttons {
-        for device_entity in activated_devices.entities() {
-            let _ = devices.get_mut(*device_entity).map(|mut status| {
-                status.0 = button.0;
-            });
-        }
+fn update_button_status(buttons: Query<(&PushButton, &mut ActivationStatus), Changed<PushButton>>) {
+    for (button, mut status) in buttons {
+        status.0 = button.0;
     }
 }
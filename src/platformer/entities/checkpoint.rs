@@ -2,10 +2,23 @@ use bevy::prelude::*;
 
 use avian2d::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
+use leafwing_input_manager::prelude::*;
 
-use sf_ui::prelude::Screen;
+use sf_events::CheckpointEvent;
+use sf_ui::prelude::{Menu, Screen};
 
-use crate::{GameLayer, platformer::level::Wall, player::death::RespawnPosition};
+use crate::{
+    Action, GameLayer, Pause,
+    platformer::{
+        level::{CurrentLevel, Wall},
+        world_state::WorldState,
+    },
+    player::{
+        death::{Dead, RespawnPoint, Spawning},
+        physics::CharacterController,
+    },
+    utils::entity_ref::ldtk_entity_position,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_ldtk_entity::<CheckpointBundle>("Checkpoint");
@@ -15,11 +28,15 @@ pub(super) fn plugin(app: &mut App) {
         (
             process_checkpoint,
             (update_restart_position, update_checkpoint_sprite).chain(),
+            open_gene_station.run_if(in_state(Menu::None).and(in_state(Pause(false)))),
         )
             .run_if(in_state(Screen::Gameplay)),
     );
 }
 
+/// Height of the checkpoint above its LDtk position.
+const CHECKPOINT_OFFSET_Y: f32 = 4.0;
+
 #[derive(Component, Reflect, Debug, Default)]
 #[reflect(Component)]
 struct Checkpoint(bool);
@@ -34,10 +51,62 @@ impl Checkpoint {
     }
 }
 
+/// How the player respawns at the checkpoint, all the fields are optional in LDtk.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+struct CheckpointSpawn {
+    facing_left: bool,
+    /// Offset of the player from the checkpoint.
+    offset: Vec2,
+    /// Jumps given back on respawn, at least one, all of them if `None`.
+    refill: Option<u32>,
+}
+
+impl CheckpointSpawn {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let facing_left = entity_instance
+            .get_maybe_enum_field("facing")
+            .ok()
+            .and_then(Option::as_ref)
+            .is_some_and(|facing| facing == "Left");
+
+        let offset_x = entity_instance
+            .get_maybe_float_field("spawn_offset_x")
+            .ok()
+            .and_then(|offset| *offset)
+            .unwrap_or(0.0);
+        let offset_y = entity_instance
+            .get_maybe_float_field("spawn_offset_y")
+            .ok()
+            .and_then(|offset| *offset)
+            .unwrap_or(0.0);
+
+        let refill = entity_instance
+            .get_maybe_int_field("refill_jumps")
+            .ok()
+            .and_then(|refill| *refill)
+            .map(|refill| {
+                // Respawning without any jump would kill the player again right away
+                if refill < 1 {
+                    warn!("Checkpoint refill_jumps must be at least 1, got {refill}");
+                }
+                refill.max(1) as u32
+            });
+
+        Self {
+            facing_left,
+            offset: Vec2::new(offset_x, offset_y),
+            refill,
+        }
+    }
+}
+
 #[derive(Bundle, LdtkEntity)]
 struct CheckpointBundle {
     #[with(Checkpoint::from_field)]
     checkpoint: Checkpoint,
+    #[with(CheckpointSpawn::from_field)]
+    spawn: CheckpointSpawn,
 
     #[sprite_sheet]
     sprite: Sprite,
@@ -47,6 +116,7 @@ impl Default for CheckpointBundle {
     fn default() -> Self {
         Self {
             checkpoint: Checkpoint(false),
+            spawn: CheckpointSpawn::default(),
             sprite: Sprite::default(),
         }
     }
@@ -85,6 +155,7 @@ struct CheckpointScanBundle {
     sensor: Sensor,
     transform: Transform,
     collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
     collisions_layer: CollisionLayers,
 }
 
@@ -97,6 +168,7 @@ impl Default for CheckpointScanBundle {
             sensor: Sensor,
             transform: Transform::from_xyz(0.0, -2.0, 0.0),
             collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
             collisions_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
         }
     }
@@ -104,11 +176,20 @@ impl Default for CheckpointScanBundle {
 
 fn process_checkpoint(
     mut commands: Commands,
-    new_entity_instance: Query<(Entity, &mut Transform), Added<Checkpoint>>,
+    world_state: Res<WorldState>,
+    new_entity_instance: Query<
+        (Entity, &EntityIid, &mut Transform, &mut Checkpoint),
+        Added<Checkpoint>,
+    >,
 ) {
-    for (entity, mut transform) in new_entity_instance {
+    for (entity, entity_iid, mut transform, mut checkpoint) in new_entity_instance {
         // Small offset to fix delta
-        transform.translation.y += 4.0;
+        transform.translation.y += CHECKPOINT_OFFSET_Y;
+
+        // Once the player reached a checkpoint, it overrides the ones active in LDtk
+        if let Some(last_checkpoint) = world_state.last_checkpoint() {
+            checkpoint.0 = last_checkpoint == entity_iid;
+        }
 
         // Spawn solid base
         commands.spawn((CheckpointBaseBundle::default(), ChildOf(entity)));
@@ -127,13 +208,39 @@ fn update_checkpoint_sprite(checkpoints: Query<(&mut Sprite, &Checkpoint), Chang
     }
 }
 
+/// Use the active checkpoint as respawn point.
+///
+/// The position is read from the LDtk project: a checkpoint restored when its level is
+/// streamed again is active on the frame it spawns, before its transform is propagated.
 fn update_restart_position(
-    checkpoints: Query<(&GlobalTransform, &Checkpoint), Changed<Checkpoint>>,
-    mut respawn_position: ResMut<RespawnPosition>,
+    checkpoints: Query<(Entity, &EntityIid, &Checkpoint, &CheckpointSpawn), Changed<Checkpoint>>,
+    parents: Query<&ChildOf>,
+    levels: Query<&LevelIid>,
+    mut respawn_point: ResMut<RespawnPoint>,
+    ldtk_project_handle: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
 ) {
-    for (transform, checkpoint) in checkpoints {
+    for (entity, entity_iid, checkpoint, spawn) in checkpoints {
         if checkpoint.0 {
-            respawn_position.0 = transform.translation().truncate();
+            let ldtk_project = ldtk_project_assets
+                .get(*ldtk_project_handle)
+                .expect("Project should be loaded if level is spawned.");
+            let Some(position) = ldtk_entity_position(ldtk_project, entity_iid) else {
+                continue;
+            };
+
+            // Entity -> layer -> level
+            let level = parents
+                .iter_ancestors(entity)
+                .find_map(|ancestor| levels.get(ancestor).ok())
+                .cloned();
+
+            *respawn_point = RespawnPoint {
+                position: position + Vec2::new(0.0, CHECKPOINT_OFFSET_Y) + spawn.offset,
+                facing_left: spawn.facing_left,
+                refill: spawn.refill,
+                level,
+            };
             return;
         }
     }
@@ -142,14 +249,64 @@ fn update_restart_position(
 fn detect_checkpoint_activation(
     trigger: On<CollisionStart>,
     checkpoints_base: Query<&ChildOf, With<CheckpointScan>>,
-    mut checkpoints: Query<(Entity, &mut Checkpoint)>,
+    mut checkpoints: Query<(Entity, &EntityIid, &GlobalTransform, &mut Checkpoint)>,
+    mut world_state: ResMut<WorldState>,
+    mut checkpoint_event: MessageWriter<CheckpointEvent>,
+    current_level: Res<CurrentLevel>,
 ) {
     if let Ok(checkpoint_entity) = checkpoints_base
         .get(trigger.event_target())
         .map(|ChildOf(parent)| parent)
     {
-        checkpoints
-            .iter_mut()
-            .for_each(|(entity, mut checkpoint)| checkpoint.0 = entity == *checkpoint_entity);
+        for (entity, entity_iid, transform, mut checkpoint) in &mut checkpoints {
+            let was_active = checkpoint.0;
+            checkpoint.0 = entity == *checkpoint_entity;
+            if checkpoint.0 {
+                world_state.activate_checkpoint(entity_iid);
+            }
+
+            if checkpoint.0 && !was_active {
+                checkpoint_event.write(CheckpointEvent {
+                    checkpoint: entity,
+                    iid: entity_iid.clone(),
+                    position: transform.translation().truncate(),
+                    level: current_level.iid.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Open the gene station when the player interacts with the active checkpoint.
+fn open_gene_station(
+    player: Single<
+        (Entity, &ActionState<Action>),
+        (With<CharacterController>, Without<Dead>, Without<Spawning>),
+    >,
+    checkpoints_scan: Query<(&CollidingEntities, &ChildOf), With<CheckpointScan>>,
+    checkpoints: Query<&Checkpoint>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut physic_time: ResMut<Time<Physics>>,
+) {
+    let (player_entity, action_state) = *player;
+    if !action_state.just_pressed(&Action::Interact) {
+        return;
+    }
+
+    let is_on_active_checkpoint =
+        checkpoints_scan
+            .iter()
+            .any(|(colliding_entities, ChildOf(parent))| {
+                colliding_entities.0.contains(&player_entity)
+                    && checkpoints
+                        .get(*parent)
+                        .is_ok_and(|checkpoint| checkpoint.0)
+            });
+
+    if is_on_active_checkpoint {
+        physic_time.pause();
+        next_pause.set(Pause(true));
+        next_menu.set(Menu::GeneStation);
     }
 }
@@ -0,0 +1,199 @@
+//! Platforms that crumble after the player lands on them and come back later
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use avian2d::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_events::RespawnEvent;
+use sf_ui::prelude::Screen;
+
+use crate::{
+    GameLayer, PausableSystems,
+    platformer::level::Wall,
+    player::physics::{CharacterController, Grounded},
+    utils::animation::{
+        AnimationState, SpriteAnimation, update_animation_atlas, update_sprite_animation,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<CrumblingPlatformBundle>("CrumblingPlatform");
+    app.add_systems(
+        Update,
+        (
+            update_animation_atlas::<CrumblingPlatformState>,
+            update_sprite_animation::<CrumblingPlatformState>,
+        ),
+    );
+    app.add_systems(
+        FixedUpdate,
+        (detect_platform_landing, update_crumbling_platforms)
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        reset_platforms_on_respawn.run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Platform that disappears a while after the player lands on it.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct CrumblingPlatform {
+    /// Time the platform shakes before vanishing.
+    shake_timer: Timer,
+    /// Time before the platform forms again.
+    respawn_timer: Timer,
+}
+
+impl CrumblingPlatform {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let shake_time = *entity_instance
+            .get_float_field("shake_time")
+            .expect("Expected crumbling platform to have shake_time field");
+        let respawn_time = *entity_instance
+            .get_float_field("respawn_time")
+            .expect("Expected crumbling platform to have respawn_time field");
+
+        Self {
+            shake_timer: Timer::from_seconds(shake_time, TimerMode::Once),
+            respawn_timer: Timer::from_seconds(respawn_time, TimerMode::Once),
+        }
+    }
+}
+
+/// The different state of the crumbling platform, also used for its animation
+#[derive(Clone, Copy, Component, Default, Reflect, PartialEq)]
+pub enum CrumblingPlatformState {
+    #[default]
+    Solid,
+    Shaking,
+    Gone,
+}
+
+impl AnimationState for CrumblingPlatformState {
+    fn get_frames(&self) -> usize {
+        match self {
+            Self::Shaking => 3,
+            _ => 1,
+        }
+    }
+
+    fn get_duration(&self) -> Option<Duration> {
+        match self {
+            Self::Shaking => Some(Duration::from_millis(80)),
+            _ => None,
+        }
+    }
+
+    fn get_start_frame(&self) -> usize {
+        match self {
+            Self::Solid => 0,
+            Self::Shaking => 1,
+            Self::Gone => 4,
+        }
+    }
+}
+
+fn platform_collider(entity_instance: &EntityInstance) -> Collider {
+    Collider::rectangle(entity_instance.width as f32, entity_instance.height as f32)
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct CrumblingPlatformBundle {
+    #[with(CrumblingPlatform::from_field)]
+    platform: CrumblingPlatform,
+    wall: Wall,
+
+    #[sprite_sheet]
+    sprite: Sprite,
+    sprite_animation: SpriteAnimation,
+    state: CrumblingPlatformState,
+
+    // Physics
+    body: RigidBody,
+    #[with(platform_collider)]
+    collider: Collider,
+    collision_layer: CollisionLayers,
+}
+
+impl Default for CrumblingPlatformBundle {
+    fn default() -> Self {
+        Self {
+            platform: CrumblingPlatform::default(),
+            wall: Wall,
+            sprite: Sprite::default(),
+            sprite_animation: SpriteAnimation::default(),
+            state: CrumblingPlatformState::Solid,
+            body: RigidBody::Static,
+            collider: Collider::rectangle(8.0, 8.0),
+            collision_layer: CollisionLayers::new(GameLayer::Ground, [GameLayer::Player]),
+        }
+    }
+}
+
+/// Start shaking the platforms the player is standing on.
+fn detect_platform_landing(
+    player: Single<&ShapeHits, (With<CharacterController>, With<Grounded>)>,
+    mut platforms: Query<(&mut CrumblingPlatform, &mut CrumblingPlatformState)>,
+) {
+    for hit in player.iter() {
+        let Ok((mut platform, mut state)) = platforms.get_mut(hit.entity) else {
+            continue;
+        };
+
+        if *state == CrumblingPlatformState::Solid {
+            platform.shake_timer.reset();
+            *state = CrumblingPlatformState::Shaking;
+        }
+    }
+}
+
+fn update_crumbling_platforms(
+    mut commands: Commands,
+    time: Res<Time>,
+    platforms: Query<(Entity, &mut CrumblingPlatform, &mut CrumblingPlatformState)>,
+) {
+    for (entity, mut platform, mut state) in platforms {
+        match *state {
+            CrumblingPlatformState::Solid => {}
+            CrumblingPlatformState::Shaking => {
+                if platform.shake_timer.tick(time.delta()).is_finished() {
+                    platform.respawn_timer.reset();
+                    *state = CrumblingPlatformState::Gone;
+                    commands.entity(entity).insert(ColliderDisabled);
+                }
+            }
+            CrumblingPlatformState::Gone => {
+                if platform.respawn_timer.tick(time.delta()).is_finished() {
+                    *state = CrumblingPlatformState::Solid;
+                    commands.entity(entity).remove::<ColliderDisabled>();
+                }
+            }
+        }
+    }
+}
+
+/// Form every platform again when the player respawns at a checkpoint.
+fn reset_platforms_on_respawn(
+    mut commands: Commands,
+    mut respawn_event: MessageReader<RespawnEvent>,
+    platforms: Query<(Entity, &mut CrumblingPlatformState), With<CrumblingPlatform>>,
+) {
+    if respawn_event.is_empty() {
+        return;
+    }
+    respawn_event.clear();
+
+    for (entity, mut state) in platforms {
+        if *state != CrumblingPlatformState::Solid {
+            *state = CrumblingPlatformState::Solid;
+            commands.entity(entity).remove::<ColliderDisabled>();
+        }
+    }
+}
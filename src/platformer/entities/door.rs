@@ -1,26 +1,51 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::Anchor};
 
 use avian2d::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 
+use sf_events::{DoorEvent, DoorState};
 use sf_ui::prelude::Screen;
 
 use crate::{
-    GameLayer,
-    platformer::entities::button::{ActivatedBy, ActivationStatus, UnresolvedActivateByRef},
+    GameLayer, PausableSystems,
+    assets::collections::LevelAssets,
+    audio::sound_effect,
+    platformer::entities::activation::{
+        ActivationStatus, ActivationSystems, UnresolvedActivateByRef,
+    },
+    platformer::world_state::{PersistentState, WorldState},
+    player::physics::CharacterController,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.register_ldtk_entity::<DoorBundle>("Door");
     app.add_systems(
         Update,
-        (process_door, update_door_on_activation).run_if(in_state(Screen::Gameplay)),
+        (process_door, update_door_sprite, play_door_sound)
+            .after(ActivationSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        FixedUpdate,
+        (update_door_on_activation, update_door)
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
     );
 }
 
+/// Time for the door to slide fully open or closed.
+const DOOR_SLIDE_SECS: f32 = 0.4;
+const DOOR_WIDTH: f32 = 8.0;
+const DOOR_HEIGHT: f32 = 32.0;
+
 #[derive(Component, Reflect, Debug, Default)]
 #[reflect(Component)]
-struct Door;
+struct Door {
+    state: DoorState,
+    /// How much the door is open, from 0.0 (closed) to 1.0 (open).
+    progress: f32,
+}
 
 #[derive(Bundle, LdtkEntity)]
 struct DoorBundle {
@@ -31,64 +56,181 @@ struct DoorBundle {
 
     #[with(UnresolvedActivateByRef::from_field)]
     unresolved_activate: UnresolvedActivateByRef,
+    status: ActivationStatus,
 
     // Physics
     body: RigidBody,
     collider: Collider,
     collision_layer: CollisionLayers,
+    collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
 }
 
 impl Default for DoorBundle {
     fn default() -> Self {
         Self {
-            door: Door,
+            door: Door::default(),
             sprite: Sprite::default(),
             unresolved_activate: UnresolvedActivateByRef::default(),
+            status: ActivationStatus(false),
             body: RigidBody::Static,
-            collider: Collider::rectangle(6.0, 32.0),
+            collider: Collider::rectangle(6.0, DOOR_HEIGHT),
             collision_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
+            collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
         }
     }
 }
 
 fn process_door(
     mut commands: Commands,
-    new_entity_instance: Query<(Entity, &mut Transform, &UnresolvedActivateByRef), Added<Door>>,
-    ldtk_entities: Query<(Entity, &EntityIid)>,
+    world_state: Res<WorldState>,
+    new_entity_instance: Query<
+        (
+            Entity,
+            &EntityIid,
+            &mut Transform,
+            &mut Door,
+            &mut ActivationStatus,
+        ),
+        Added<Door>,
+    >,
 ) {
-    for (entity, mut transform, unresolved_ref) in new_entity_instance {
+    for (entity, entity_iid, mut transform, mut door, mut status) in new_entity_instance {
         // Small offset to fix delta
         transform.translation.y += 4.0;
 
-        if let Some(activater_iid) = unresolved_ref.0.as_ref() {
-            let (activater_entity, _) = ldtk_entities
-                .iter()
-                .find(|(_, iid)| *iid == activater_iid)
-                .expect("Activater entity should exists");
-
-            commands
-                .entity(entity)
-                .remove::<UnresolvedActivateByRef>()
-                .insert(ActivatedBy(activater_entity));
-        } else {
-            commands.entity(entity).remove::<UnresolvedActivateByRef>();
+        // Doors left open are spawned open instead of sliding again
+        if world_state.is_active(entity_iid) == Some(true) {
+            door.state = DoorState::Open;
+            door.progress = 1.0;
+            status.0 = true;
+            commands.entity(entity).insert(Sensor);
         }
     }
 }
 
 fn update_door_on_activation(
     mut commands: Commands,
-    doors: Query<(Entity, &mut Sprite, &ActivationStatus), (With<Door>, Changed<ActivationStatus>)>,
+    mut world_state: ResMut<WorldState>,
+    mut door_event: MessageWriter<DoorEvent>,
+    doors: Query<(Entity, &EntityIid, &mut Door, &ActivationStatus), Changed<ActivationStatus>>,
+) {
+    for (entity, entity_iid, mut door, status) in doors {
+        let new_state = match (status.0, door.state) {
+            (true, DoorState::Closed | DoorState::Closing) => DoorState::Opening,
+            (false, DoorState::Open | DoorState::Opening) => DoorState::Closing,
+            _ => continue,
+        };
+
+        // The door can be crossed as soon as it starts moving
+        if new_state == DoorState::Opening {
+            commands.entity(entity).insert(Sensor);
+        }
+
+        door.state = new_state;
+        world_state.set(entity_iid, PersistentState::Active(status.0));
+        door_event.write(DoorEvent {
+            door: entity,
+            iid: entity_iid.clone(),
+            state: new_state,
+        });
+    }
+}
+
+/// Slide the doors and reopen the closing ones if the player is in the way.
+///
+/// A door reopened by the player closes again once it is free if its activator is off.
+fn update_door(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut door_event: MessageWriter<DoorEvent>,
+    doors: Query<(
+        Entity,
+        &EntityIid,
+        &mut Door,
+        &ActivationStatus,
+        &CollidingEntities,
+    )>,
+    player: Single<Entity, With<CharacterController>>,
+) {
+    let delta = time.delta_secs() / DOOR_SLIDE_SECS;
+
+    for (entity, entity_iid, mut door, status, colliding_entities) in doors {
+        let is_blocked = colliding_entities.0.contains(&*player);
+        let previous_state = door.state;
+
+        match door.state {
+            DoorState::Opening => {
+                door.progress = (door.progress + delta).min(1.0);
+                if door.progress >= 1.0 {
+                    door.state = DoorState::Open;
+                }
+            }
+            DoorState::Closing if is_blocked => door.state = DoorState::Opening,
+            DoorState::Closing => {
+                door.progress = (door.progress - delta).max(0.0);
+                if door.progress <= 0.0 {
+                    door.state = DoorState::Closed;
+                    commands.entity(entity).remove::<Sensor>();
+                }
+            }
+            DoorState::Open if !status.0 && !is_blocked => door.state = DoorState::Closing,
+            DoorState::Closed | DoorState::Open => {}
+        }
+
+        if door.state != previous_state {
+            door_event.write(DoorEvent {
+                door: entity,
+                iid: entity_iid.clone(),
+                state: door.state,
+            });
+        }
+    }
+}
+
+/// Play the sliding sound each time a door starts to move.
+fn play_door_sound(
+    mut commands: Commands,
+    mut door_event: MessageReader<DoorEvent>,
+    level_assets: Res<LevelAssets>,
 ) {
-    for (entity, mut sprite, status) in doors {
-        if let Some(atlas) = sprite.texture_atlas.as_mut() {
-            if status.0 {
-                commands.entity(entity).insert(Sensor);
-                atlas.index = 1;
-            } else {
-                commands.entity(entity).remove::<Sensor>();
-                atlas.index = 0;
-            };
+    for event in door_event.read() {
+        if matches!(event.state, DoorState::Opening | DoorState::Closing) {
+            commands.spawn((
+                Name::new("Door Sound"),
+                sound_effect(level_assets.door_sound.clone()),
+            ));
         }
     }
 }
+
+/// Show the part of the door that is not yet in the ceiling.
+fn update_door_sprite(doors: Query<(&mut Sprite, &mut Anchor, &Door), Changed<Door>>) {
+    for (mut sprite, mut anchor, door) in doors {
+        let Some(atlas) = sprite.texture_atlas.as_mut() else {
+            continue;
+        };
+
+        if door.progress <= 0.0 || door.progress >= 1.0 {
+            atlas.index = if door.progress >= 1.0 { 1 } else { 0 };
+            sprite.rect = None;
+            *anchor = Anchor::CENTER;
+            continue;
+        }
+
+        // Keep the top of the visible part at the top of the door
+        let visible_height = (1.0 - door.progress) * DOOR_HEIGHT;
+        atlas.index = 0;
+        sprite.rect = Some(Rect::new(
+            0.0,
+            DOOR_HEIGHT - visible_height,
+            DOOR_WIDTH,
+            DOOR_HEIGHT,
+        ));
+        *anchor = Anchor::from(Vec2::new(
+            0.0,
+            -(DOOR_HEIGHT - visible_height) / (2.0 * visible_height),
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+//! Entrances warping the player to another entrance, possibly in a non-adjacent level
+
+use bevy::prelude::*;
+
+use avian2d::{math::*, prelude::*};
+use bevy_ecs_ldtk::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use sf_ui::prelude::Screen;
+
+use crate::{
+    Action, GameLayer, PausableSystems,
+    camera::{MainCamera, clamp_to_level},
+    platformer::{
+        entities::activation::{ActivationStatus, UnresolvedActivateByRef},
+        level::{CurrentLevel, level_world_bounds},
+    },
+    player::{
+        death::{Dead, PendingPlacement, Spawning},
+        movement::MovementModifierSystems,
+        physics::{CharacterController, ExternalVelocity},
+    },
+    utils::entity_ref::{UnresolvedEntityRefs, ldtk_entity_position},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<EntranceBundle>("Entrance");
+    app.init_resource::<WarpArrival>();
+    app.add_systems(
+        FixedUpdate,
+        use_entrance
+            .in_set(MovementModifierSystems::Entrances)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Distance the player must walk away from the arrival before the touch entrances work again.
+const ARRIVAL_RADIUS: Scalar = 12.0;
+
+/// Entrance linked to another one.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Entrance {
+    destination: EntityIid,
+    destination_level: LevelIid,
+    /// Warp as soon as the player touches it instead of waiting for [`Action::Interact`].
+    on_touch: bool,
+}
+
+impl Entrance {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let destination = entity_instance
+            .get_entity_ref_field("destination")
+            .expect("Expected entrance to have destination entity ref field");
+        let on_touch = *entity_instance
+            .get_bool_field("on_touch")
+            .expect("Expected entrance to have on_touch field");
+
+        Self {
+            destination: EntityIid::new(destination.entity_iid.clone()),
+            destination_level: LevelIid::new(destination.level_iid.clone()),
+            on_touch,
+        }
+    }
+}
+
+/// Where the player arrived with the last warp.
+///
+/// Prevents the touch entrance at the destination from sending the player back.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct WarpArrival(Option<Vector>);
+
+fn entrance_collider(entity_instance: &EntityInstance) -> Collider {
+    Collider::rectangle(entity_instance.width as f32, entity_instance.height as f32)
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct EntranceBundle {
+    #[with(Entrance::from_field)]
+    entrance: Entrance,
+    /// Entrances without activator are always open.
+    #[with(UnresolvedActivateByRef::from_field)]
+    unresolved_activate: UnresolvedActivateByRef,
+    status: ActivationStatus,
+
+    #[sprite_sheet]
+    sprite: Sprite,
+
+    // Physics
+    body: RigidBody,
+    #[with(entrance_collider)]
+    collider: Collider,
+    sensor: Sensor,
+    collision_layer: CollisionLayers,
+    collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
+}
+
+impl Default for EntranceBundle {
+    fn default() -> Self {
+        Self {
+            entrance: Entrance::default(),
+            unresolved_activate: UnresolvedActivateByRef::default(),
+            status: ActivationStatus(false),
+            sprite: Sprite::default(),
+            body: RigidBody::Static,
+            collider: Collider::rectangle(8.0, 16.0),
+            sensor: Sensor,
+            collision_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
+            collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
+        }
+    }
+}
+
+fn use_entrance(
+    mut commands: Commands,
+    entrances: Query<(
+        &Entrance,
+        &UnresolvedActivateByRef,
+        &ActivationStatus,
+        &CollidingEntities,
+    )>,
+    player: Single<
+        (
+            Entity,
+            &ActionState<Action>,
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut ExternalVelocity,
+        ),
+        (With<CharacterController>, Without<Dead>, Without<Spawning>),
+    >,
+    mut camera: Single<&mut Transform, (With<MainCamera>, Without<CharacterController>)>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut warp_arrival: ResMut<WarpArrival>,
+    ldtk_project_handle: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    let (player_entity, action_state, mut transform, mut linear_velocity, mut external_velocity) =
+        player.into_inner();
+
+    if warp_arrival
+        .0
+        .is_some_and(|arrival| arrival.distance(transform.translation.truncate()) > ARRIVAL_RADIUS)
+    {
+        warp_arrival.0 = None;
+    }
+
+    let Some(entrance) = entrances
+        .iter()
+        .find(|(entrance, activate_ref, status, colliding_entities)| {
+            let is_open = activate_ref.refs().is_empty() || status.0;
+            let is_used = if entrance.on_touch {
+                warp_arrival.0.is_none()
+            } else {
+                action_state.just_pressed(&Action::Interact)
+            };
+
+            is_open && is_used && colliding_entities.0.contains(&player_entity)
+        })
+        .map(|(entrance, ..)| entrance)
+    else {
+        return;
+    };
+
+    let ldtk_project = ldtk_project_assets
+        .get(*ldtk_project_handle)
+        .expect("Project should be loaded during gameplay.");
+
+    let (Some(destination), Some(level)) = (
+        ldtk_entity_position(ldtk_project, &entrance.destination),
+        ldtk_project.get_raw_level_by_iid(&entrance.destination_level.to_string()),
+    ) else {
+        warn!(
+            "Entrance leads to the LDtk entity {} that doesn't exist",
+            entrance.destination.as_str()
+        );
+        return;
+    };
+
+    linear_velocity.0 = Vector::ZERO;
+    external_velocity.0 = 0.0;
+    warp_arrival.0 = Some(destination);
+
+    if current_level.iid.as_ref() == Some(&entrance.destination_level) {
+        // No transition inside a level, the camera jumps to the player
+        transform.translation = destination.extend(transform.translation.z);
+        let position = clamp_to_level(destination, current_level.bounds);
+        camera.translation = position.extend(camera.translation.z);
+    } else {
+        // Select the level now, the player is placed once it is spawned
+        *current_level = CurrentLevel {
+            iid: Some(entrance.destination_level.clone()),
+            bounds: level_world_bounds(level),
+        };
+        commands.insert_resource(PendingPlacement {
+            position: destination,
+            level: Some(entrance.destination_level.clone()),
+            is_respawn: false,
+        });
+        commands.entity(player_entity).insert(RigidBodyDisabled);
+    }
+}
@@ -0,0 +1,177 @@
+//! Zones pushing the player around (wind, conveyors)
+
+use bevy::prelude::*;
+
+use avian2d::{math::*, prelude::*};
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_ui::prelude::Screen;
+
+use crate::{
+    GameLayer, PausableSystems,
+    player::{
+        death::Dead,
+        movement::MovementModifierSystems,
+        physics::{CharacterController, ExternalVelocity, Grounded},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<WindBundle>("Wind");
+    app.register_ldtk_entity::<ConveyorBundle>("Conveyor");
+    app.add_systems(
+        FixedUpdate,
+        apply_force_zones
+            .in_set(MovementModifierSystems::ForceZones)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Fraction of the external velocity lost per second in the air.
+const AIR_DRAG: Scalar = 2.0;
+/// Fraction of the external velocity lost per second on the ground.
+const GROUND_DRAG: Scalar = 20.0;
+
+/// Zone applying an external contribution to the velocity of the player.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub enum ForceZone {
+    /// Constant acceleration applied while inside the zone.
+    Wind(Vector),
+    /// Horizontal velocity given while standing in the zone.
+    Conveyor(Scalar),
+}
+
+impl Default for ForceZone {
+    fn default() -> Self {
+        Self::Wind(Vector::ZERO)
+    }
+}
+
+impl ForceZone {
+    fn wind_from_field(entity_instance: &EntityInstance) -> Self {
+        let acceleration_x = *entity_instance
+            .get_float_field("acceleration_x")
+            .expect("Expected wind to have acceleration_x field");
+        let acceleration_y = *entity_instance
+            .get_float_field("acceleration_y")
+            .expect("Expected wind to have acceleration_y field");
+
+        Self::Wind(Vector::new(acceleration_x, acceleration_y))
+    }
+
+    fn conveyor_from_field(entity_instance: &EntityInstance) -> Self {
+        let speed = *entity_instance
+            .get_float_field("speed")
+            .expect("Expected conveyor to have speed field");
+
+        Self::Conveyor(speed)
+    }
+}
+
+fn zone_collider(entity_instance: &EntityInstance) -> Collider {
+    Collider::rectangle(entity_instance.width as f32, entity_instance.height as f32)
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct WindBundle {
+    #[with(ForceZone::wind_from_field)]
+    force_zone: ForceZone,
+
+    // Physics
+    body: RigidBody,
+    #[with(zone_collider)]
+    collider: Collider,
+    sensor: Sensor,
+    collision_layer: CollisionLayers,
+    collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
+}
+
+impl Default for WindBundle {
+    fn default() -> Self {
+        Self {
+            force_zone: ForceZone::default(),
+            body: RigidBody::Static,
+            collider: Collider::rectangle(8.0, 8.0),
+            sensor: Sensor,
+            collision_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
+            collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
+        }
+    }
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct ConveyorBundle {
+    #[with(ForceZone::conveyor_from_field)]
+    force_zone: ForceZone,
+
+    // Physics
+    body: RigidBody,
+    #[with(zone_collider)]
+    collider: Collider,
+    sensor: Sensor,
+    collision_layer: CollisionLayers,
+    collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
+}
+
+impl Default for ConveyorBundle {
+    fn default() -> Self {
+        Self {
+            force_zone: ForceZone::Conveyor(0.0),
+            body: RigidBody::Static,
+            collider: Collider::rectangle(8.0, 8.0),
+            sensor: Sensor,
+            collision_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
+            collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
+        }
+    }
+}
+
+/// Accumulate the contributions of the zones the player is in into its [`ExternalVelocity`].
+fn apply_force_zones(
+    time: Res<Time>,
+    zones: Query<(&CollidingEntities, &ForceZone)>,
+    player: Single<
+        (
+            Entity,
+            &mut ExternalVelocity,
+            &mut LinearVelocity,
+            Has<Grounded>,
+        ),
+        (With<CharacterController>, Without<Dead>),
+    >,
+) {
+    let delta_time = time.delta_secs();
+    let (player_entity, mut external_velocity, mut linear_velocity, is_grounded) =
+        player.into_inner();
+
+    let mut acceleration = Vector::ZERO;
+    let mut surface_velocity = None;
+    for (colliding_entities, zone) in zones {
+        if !colliding_entities.0.contains(&player_entity) {
+            continue;
+        }
+
+        match zone {
+            ForceZone::Wind(wind) => acceleration += *wind,
+            ForceZone::Conveyor(speed) if is_grounded => surface_velocity = Some(*speed),
+            ForceZone::Conveyor(_) => {}
+        }
+    }
+
+    if let Some(speed) = surface_velocity {
+        external_velocity.0 = speed;
+    } else {
+        let drag = if is_grounded { GROUND_DRAG } else { AIR_DRAG };
+        external_velocity.0 *= (1.0 - drag * delta_time).max(0.0);
+    }
+    external_velocity.0 += acceleration.x * delta_time;
+
+    // The vertical velocity is integrated by the gravity, so the wind is applied directly.
+    linear_velocity.y += acceleration.y * delta_time;
+}
@@ -0,0 +1,223 @@
+//! Orbs that restore the jumps of the player on touch
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use avian2d::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_events::RespawnEvent;
+use sf_ui::prelude::Screen;
+
+use crate::{
+    GameLayer, PausableSystems,
+    platformer::world_state::{PersistentState, WorldState},
+    player::{
+        death::Dead,
+        movement::{JumpAmount, MovementModifierSystems},
+        physics::CharacterController,
+    },
+    utils::animation::{
+        AnimationState, SpriteAnimation, update_animation_atlas, update_sprite_animation,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<JumpOrbBundle>("JumpOrb");
+    app.add_systems(
+        Update,
+        (
+            update_animation_atlas::<JumpOrbAnimationState>,
+            update_sprite_animation::<JumpOrbAnimationState>,
+        ),
+    );
+    app.add_systems(
+        FixedUpdate,
+        (detect_jump_orb_pickup, tick_jump_orb_respawn)
+            .chain()
+            .in_set(MovementModifierSystems::Pickups)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        (process_jump_orb, reset_jump_orbs_on_respawn).run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Orb that gives back jumps to the player.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct JumpOrb {
+    /// Number of jumps restored, `None` for a full refill.
+    amount: Option<u32>,
+    /// Time before the orb comes back, `None` if it never does.
+    respawn_timer: Option<Timer>,
+}
+
+impl JumpOrb {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let amount = entity_instance
+            .get_maybe_int_field("amount")
+            .expect("Expected jump orb to have amount field")
+            .map(|amount| amount.max(0) as u32);
+
+        let respawn_timer = entity_instance
+            .get_maybe_float_field("respawn_time")
+            .expect("Expected jump orb to have respawn_time field")
+            .map(|seconds| Timer::from_seconds(seconds, TimerMode::Once));
+
+        Self {
+            amount,
+            respawn_timer,
+        }
+    }
+
+    /// Give back the jumps of this orb.
+    fn refill(&self, jump_amount: &mut JumpAmount) {
+        match self.amount {
+            Some(amount) => jump_amount.refill(amount),
+            None => jump_amount.reset(),
+        }
+    }
+}
+
+/// The different state of the animation for the jump orb
+#[derive(Clone, Copy, Component, Default, Reflect, PartialEq)]
+pub enum JumpOrbAnimationState {
+    #[default]
+    Idle,
+    Consumed,
+}
+
+impl AnimationState for JumpOrbAnimationState {
+    fn get_frames(&self) -> usize {
+        match self {
+            Self::Idle => 4,
+            Self::Consumed => 1,
+        }
+    }
+
+    fn get_duration(&self) -> Option<Duration> {
+        match self {
+            Self::Idle => Some(Duration::from_millis(150)),
+            Self::Consumed => None,
+        }
+    }
+
+    fn get_start_frame(&self) -> usize {
+        match self {
+            Self::Idle => 0,
+            Self::Consumed => 4,
+        }
+    }
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct JumpOrbBundle {
+    #[with(JumpOrb::from_field)]
+    jump_orb: JumpOrb,
+
+    #[sprite_sheet]
+    sprite: Sprite,
+    sprite_animation: SpriteAnimation,
+    animation_state: JumpOrbAnimationState,
+
+    // Physics
+    body: RigidBody,
+    collider: Collider,
+    sensor: Sensor,
+    collision_layer: CollisionLayers,
+    collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
+}
+
+impl Default for JumpOrbBundle {
+    fn default() -> Self {
+        Self {
+            jump_orb: JumpOrb::default(),
+            sprite: Sprite::default(),
+            sprite_animation: SpriteAnimation::default(),
+            animation_state: JumpOrbAnimationState::Idle,
+            body: RigidBody::Static,
+            collider: Collider::circle(3.0),
+            sensor: Sensor,
+            collision_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
+            collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
+        }
+    }
+}
+
+/// Keep consumed the orbs that never come back.
+fn process_jump_orb(
+    world_state: Res<WorldState>,
+    orbs: Query<(&EntityIid, &mut JumpOrbAnimationState), Added<JumpOrb>>,
+) {
+    for (entity_iid, mut state) in orbs {
+        if world_state.is_collected(entity_iid) {
+            *state = JumpOrbAnimationState::Consumed;
+        }
+    }
+}
+
+fn detect_jump_orb_pickup(
+    orbs: Query<(
+        &EntityIid,
+        &CollidingEntities,
+        &mut JumpOrb,
+        &mut JumpOrbAnimationState,
+    )>,
+    player: Single<(Entity, &mut JumpAmount), (With<CharacterController>, Without<Dead>)>,
+    mut world_state: ResMut<WorldState>,
+) {
+    let (player_entity, mut jump_amount) = player.into_inner();
+
+    for (entity_iid, colliding_entities, mut orb, mut state) in orbs {
+        if *state != JumpOrbAnimationState::Idle
+            || !colliding_entities.0.contains(&player_entity)
+            || jump_amount.remaining >= jump_amount.max
+        {
+            continue;
+        }
+
+        orb.refill(&mut jump_amount);
+        match orb.respawn_timer.as_mut() {
+            Some(timer) => timer.reset(),
+            None => world_state.set(entity_iid, PersistentState::Collected),
+        }
+        *state = JumpOrbAnimationState::Consumed;
+    }
+}
+
+fn tick_jump_orb_respawn(time: Res<Time>, orbs: Query<(&mut JumpOrb, &mut JumpOrbAnimationState)>) {
+    for (mut orb, mut state) in orbs {
+        if *state != JumpOrbAnimationState::Consumed {
+            continue;
+        }
+
+        if let Some(timer) = orb.respawn_timer.as_mut()
+            && timer.tick(time.delta()).is_finished()
+        {
+            *state = JumpOrbAnimationState::Idle;
+        }
+    }
+}
+
+/// Bring back every orb when the player respawns, even the ones that never come back by themselves.
+fn reset_jump_orbs_on_respawn(
+    mut respawn_event: MessageReader<RespawnEvent>,
+    orbs: Query<&mut JumpOrbAnimationState, With<JumpOrb>>,
+    mut world_state: ResMut<WorldState>,
+) {
+    if respawn_event.is_empty() {
+        return;
+    }
+    respawn_event.clear();
+
+    for mut state in orbs {
+        *state = JumpOrbAnimationState::Idle;
+    }
+    world_state.clear_collected();
+}
@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+
+use avian2d::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use sf_events::ButtonEvent;
+use sf_ui::prelude::Screen;
+
+use crate::{
+    Action, GameLayer, PausableSystems,
+    platformer::{
+        entities::activation::{ActivationStatus, ActivationSystems},
+        world_state::{PersistentState, WorldState},
+    },
+    player::{
+        death::{Dead, Spawning},
+        movement::{MovementModifierSystems, MovementSystems},
+        physics::CharacterController,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<LeverBundle>("Lever");
+    app.add_systems(
+        Update,
+        (process_lever, update_sprite_lever, update_lever_status)
+            .chain()
+            .before(ActivationSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        FixedUpdate,
+        toggle_lever
+            .after(MovementModifierSystems::Inputs)
+            .before(MovementSystems)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Switch toggled by the player with [`Action::Interact`].
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Lever(pub bool);
+
+impl Lever {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let active = entity_instance
+            .get_bool_field("active")
+            .expect("Expected lever to have active field");
+
+        Self(*active)
+    }
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct LeverBundle {
+    #[with(Lever::from_field)]
+    lever: Lever,
+    status: ActivationStatus,
+    #[sprite_sheet]
+    sprite: Sprite,
+
+    // Physics
+    body: RigidBody,
+    collider: Collider,
+    sensor: Sensor,
+    collision_layer: CollisionLayers,
+    collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
+}
+
+impl Default for LeverBundle {
+    fn default() -> Self {
+        Self {
+            lever: Lever(false),
+            status: ActivationStatus(false),
+            sprite: Sprite::default(),
+            body: RigidBody::Static,
+            collider: Collider::rectangle(8.0, 8.0),
+            sensor: Sensor,
+            collision_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
+            collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
+        }
+    }
+}
+
+/// Put back the levers in the state the player left them.
+fn process_lever(
+    world_state: Res<WorldState>,
+    levers: Query<(&EntityIid, &mut Lever), Added<Lever>>,
+) {
+    for (entity_iid, mut lever) in levers {
+        if let Some(active) = world_state.is_active(entity_iid) {
+            lever.0 = active;
+        }
+    }
+}
+
+fn toggle_lever(
+    player: Single<
+        (Entity, &ActionState<Action>),
+        (With<CharacterController>, Without<Dead>, Without<Spawning>),
+    >,
+    levers: Query<(Entity, &EntityIid, &CollidingEntities, &mut Lever)>,
+    mut world_state: ResMut<WorldState>,
+    mut button_event: MessageWriter<ButtonEvent>,
+) {
+    let (player_entity, action_state) = *player;
+    if !action_state.just_pressed(&Action::Interact) {
+        return;
+    }
+
+    for (entity, entity_iid, colliding_entities, mut lever) in levers {
+        if colliding_entities.0.contains(&player_entity) {
+            lever.0 = !lever.0;
+            world_state.set(entity_iid, PersistentState::Active(lever.0));
+            button_event.write(ButtonEvent {
+                button: entity,
+                iid: entity_iid.clone(),
+                pressed: lever.0,
+            });
+        }
+    }
+}
+
+fn update_sprite_lever(query: Query<(&mut Sprite, &Lever), Changed<Lever>>) {
+    for (mut sprite, lever) in query {
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.index = if lever.0 { 1 } else { 0 };
+        }
+    }
+}
+
+fn update_lever_status(levers: Query<(&Lever, &mut ActivationStatus), Changed<Lever>>) {
+    for (lever, mut status) in levers {
+        status.0 = lever.0;
+    }
+}
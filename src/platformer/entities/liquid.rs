@@ -0,0 +1,133 @@
+//! Liquid volumes in which the player swims
+
+use bevy::prelude::*;
+
+use avian2d::{math::*, prelude::*};
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_ui::prelude::Screen;
+
+use crate::{
+    GameLayer, PausableSystems,
+    player::{
+        movement::MovementSystems,
+        physics::{CharacterController, Submerged},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<LiquidBundle>("Liquid");
+    app.add_systems(
+        FixedUpdate,
+        update_submerged
+            .before(MovementSystems)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const LIQUID_COLOR: Color = Color::srgba(0.2, 0.4, 0.8, 0.5);
+
+/// Rectangle of liquid replacing the gravity of the player by drag and buoyancy.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Liquid {
+    buoyancy: Scalar,
+    drag: Scalar,
+    speed_factor: Scalar,
+    height: Scalar,
+}
+
+impl Liquid {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let buoyancy = *entity_instance
+            .get_float_field("buoyancy")
+            .expect("Expected liquid to have buoyancy field");
+        let drag = *entity_instance
+            .get_float_field("drag")
+            .expect("Expected liquid to have drag field");
+        let speed_factor = *entity_instance
+            .get_float_field("speed_factor")
+            .expect("Expected liquid to have speed_factor field");
+
+        Self {
+            buoyancy,
+            drag,
+            speed_factor,
+            height: entity_instance.height as Scalar,
+        }
+    }
+}
+
+fn liquid_collider(entity_instance: &EntityInstance) -> Collider {
+    Collider::rectangle(entity_instance.width as f32, entity_instance.height as f32)
+}
+
+fn liquid_sprite(entity_instance: &EntityInstance) -> Sprite {
+    Sprite::from_color(
+        LIQUID_COLOR,
+        Vec2::new(entity_instance.width as f32, entity_instance.height as f32),
+    )
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct LiquidBundle {
+    #[with(Liquid::from_field)]
+    liquid: Liquid,
+
+    #[with(liquid_sprite)]
+    sprite: Sprite,
+
+    // Physics
+    body: RigidBody,
+    #[with(liquid_collider)]
+    collider: Collider,
+    sensor: Sensor,
+    collision_layer: CollisionLayers,
+    collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
+}
+
+impl Default for LiquidBundle {
+    fn default() -> Self {
+        Self {
+            liquid: Liquid::default(),
+            sprite: Sprite::default(),
+            body: RigidBody::Static,
+            collider: Collider::rectangle(8.0, 8.0),
+            sensor: Sensor,
+            collision_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
+            collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
+        }
+    }
+}
+
+/// Updates the [`Submerged`] status of the player.
+fn update_submerged(
+    mut commands: Commands,
+    liquids: Query<(&CollidingEntities, &GlobalTransform, &Liquid)>,
+    player: Single<(Entity, &GlobalTransform), With<CharacterController>>,
+) {
+    let (player_entity, player_transform) = *player;
+
+    let submerged = liquids
+        .iter()
+        .find(|(colliding_entities, ..)| colliding_entities.0.contains(&player_entity))
+        .map(|(_, liquid_transform, liquid)| {
+            let surface = liquid_transform.translation().y + liquid.height / 2.0;
+
+            Submerged {
+                buoyancy: liquid.buoyancy,
+                drag: liquid.drag,
+                speed_factor: liquid.speed_factor,
+                depth: surface - player_transform.translation().y,
+            }
+        });
+
+    if let Some(submerged) = submerged {
+        commands.entity(player_entity).insert(submerged);
+    } else {
+        commands.entity(player_entity).remove::<Submerged>();
+    }
+}
@@ -0,0 +1,412 @@
+//! Logic entities (gate, delay, latch, timer) placed between activators and devices
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_ui::prelude::Screen;
+
+use crate::{
+    platformer::{
+        entities::activation::{ActivationStatus, ActivationSystems},
+        world_state::{PersistentState, WorldState},
+    },
+    utils::entity_ref::{UnresolvedEntityRefs, resolve_entity_refs},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<LogicGateBundle>("LogicGate");
+    app.register_ldtk_entity::<LogicDelayBundle>("LogicDelay");
+    app.register_ldtk_entity::<LogicLatchBundle>("LogicLatch");
+    app.register_ldtk_entity::<LogicTimerBundle>("LogicTimer");
+    app.add_systems(
+        Update,
+        (
+            resolve_entity_refs::<UnresolvedLogicInputsRef>,
+            process_logic_latch,
+        )
+            .before(ActivationSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How the inputs of a logic entity are combined.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum LogicOperation {
+    And,
+    #[default]
+    Or,
+    Xor,
+    /// Active when no input is active.
+    Not,
+}
+
+impl LogicOperation {
+    /// Read the optional `logic` enum field, [`LogicOperation::Or`] if missing.
+    pub fn from_field(entity_instance: &EntityInstance) -> Self {
+        entity_instance
+            .get_maybe_enum_field("logic")
+            .ok()
+            .and_then(|name| name.as_deref())
+            .map(Self::from_name)
+            .unwrap_or_default()
+    }
+
+    fn from_name(name: &str) -> Self {
+        match name {
+            "And" => Self::And,
+            "Xor" => Self::Xor,
+            "Not" => Self::Not,
+            _ => Self::Or,
+        }
+    }
+
+    fn combine(&self, inputs: &[bool]) -> bool {
+        match self {
+            Self::And => !inputs.is_empty() && inputs.iter().all(|input| *input),
+            Self::Or => inputs.iter().any(|input| *input),
+            Self::Xor => inputs.iter().filter(|input| **input).count() % 2 == 1,
+            Self::Not => !inputs.iter().any(|input| *input),
+        }
+    }
+}
+
+/// Activators read by a logic entity, or by a device with several activators.
+#[derive(Component, Debug, Default)]
+#[require(ActivationStatus)]
+pub struct LogicInputs(pub Vec<Entity>);
+
+/// Component to handle unresolved logic inputs at ldtk startup
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+struct UnresolvedLogicInputsRef(Vec<EntityIid>);
+
+impl UnresolvedLogicInputsRef {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        Self(
+            entity_instance
+                .get_maybe_entity_refs_field("inputs")
+                .expect("Expected logic entity to have inputs entity refs field")
+                .iter()
+                .flatten()
+                .map(|entity_ref| EntityIid::new(entity_ref.entity_iid.clone()))
+                .collect(),
+        )
+    }
+}
+
+/// Follow the combined inputs after a delay.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct LogicDelay {
+    delay: f32,
+    /// Input changes waiting to be emitted.
+    pending: VecDeque<(Timer, bool)>,
+    last_input: bool,
+}
+
+impl LogicDelay {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let delay = *entity_instance
+            .get_float_field("delay")
+            .expect("Expected logic delay to have delay field");
+
+        Self { delay, ..default() }
+    }
+
+    /// Return the output once the delay of the changes of the input elapsed.
+    fn update(&mut self, input: bool, current: bool, delta: Duration) -> bool {
+        if input != self.last_input {
+            self.last_input = input;
+            let timer = Timer::from_seconds(self.delay, TimerMode::Once);
+            self.pending.push_back((timer, input));
+        }
+
+        let mut output = current;
+        for (timer, _) in self.pending.iter_mut() {
+            timer.tick(delta);
+        }
+        while self
+            .pending
+            .front()
+            .is_some_and(|(timer, _)| timer.is_finished())
+        {
+            if let Some((_, value)) = self.pending.pop_front() {
+                output = value;
+            }
+        }
+        output
+    }
+}
+
+/// Toggle each time the combined inputs become active.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct LogicLatch {
+    /// Input of the last evaluation, `None` until the first one.
+    ///
+    /// A latch spawned again with an active input must not toggle.
+    last_input: Option<bool>,
+}
+
+impl LogicLatch {
+    /// Return the output, toggled on the rising edges of the input.
+    fn update(&mut self, input: bool, current: bool) -> bool {
+        let is_rising = input && self.last_input == Some(false);
+        self.last_input = Some(input);
+        current ^ is_rising
+    }
+}
+
+/// Stay active for a duration each time the combined inputs become active.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct LogicTimer {
+    timer: Timer,
+    last_input: bool,
+}
+
+impl LogicTimer {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let duration = *entity_instance
+            .get_float_field("duration")
+            .expect("Expected logic timer to have duration field");
+
+        let mut timer = Timer::from_seconds(duration, TimerMode::Once);
+        timer.tick(timer.duration());
+
+        Self {
+            timer,
+            last_input: false,
+        }
+    }
+
+    /// Return the output, active for the duration after each rising edge of the input.
+    fn update(&mut self, input: bool, delta: Duration) -> bool {
+        if input && !self.last_input {
+            self.timer.reset();
+        }
+        self.last_input = input;
+        self.timer.tick(delta);
+        !self.timer.is_finished()
+    }
+}
+
+impl UnresolvedEntityRefs for UnresolvedLogicInputsRef {
+    fn refs(&self) -> &[EntityIid] {
+        &self.0
+    }
+
+    fn resolve(&self, commands: &mut Commands, entity: Entity, inputs: Vec<Entity>) {
+        commands.entity(entity).insert(LogicInputs(inputs));
+    }
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct LogicBaseBundle {
+    #[with(LogicOperation::from_field)]
+    operation: LogicOperation,
+    #[with(UnresolvedLogicInputsRef::from_field)]
+    unresolved_inputs: UnresolvedLogicInputsRef,
+    status: ActivationStatus,
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct LogicGateBundle {
+    #[ldtk_entity]
+    base: LogicBaseBundle,
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct LogicDelayBundle {
+    #[ldtk_entity]
+    base: LogicBaseBundle,
+    #[with(LogicDelay::from_field)]
+    delay: LogicDelay,
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct LogicLatchBundle {
+    #[ldtk_entity]
+    base: LogicBaseBundle,
+    latch: LogicLatch,
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+struct LogicTimerBundle {
+    #[ldtk_entity]
+    base: LogicBaseBundle,
+    #[with(LogicTimer::from_field)]
+    timer: LogicTimer,
+}
+
+/// Put back the latches in the state the player left them.
+fn process_logic_latch(
+    world_state: Res<WorldState>,
+    latches: Query<(&EntityIid, &mut ActivationStatus), Added<LogicLatch>>,
+) {
+    for (entity_iid, mut status) in latches {
+        if let Some(active) = world_state.is_active(entity_iid) {
+            status.0 = active;
+        }
+    }
+}
+
+/// Compute the status of every logic entity from the status of its inputs.
+///
+/// Logic entities can be chained, each one adding a frame of latency.
+pub(super) fn evaluate_logic(
+    time: Res<Time>,
+    mut world_state: ResMut<WorldState>,
+    logic_entities: Query<(
+        Entity,
+        &EntityIid,
+        &LogicInputs,
+        &LogicOperation,
+        Option<&mut LogicDelay>,
+        Option<&mut LogicLatch>,
+        Option<&mut LogicTimer>,
+    )>,
+    mut statuses: Query<&mut ActivationStatus>,
+) {
+    for (entity, entity_iid, inputs, operation, delay, latch, timer) in logic_entities {
+        // Hold the output while an input is in a despawned level
+        let Ok(input_statuses) = inputs
+            .0
+            .iter()
+            .map(|input_entity| statuses.get(*input_entity).map(|status| status.0))
+            .collect::<Result<Vec<bool>, _>>()
+        else {
+            continue;
+        };
+        let input = operation.combine(&input_statuses);
+
+        let Ok(current) = statuses.get(entity).map(|status| status.0) else {
+            continue;
+        };
+
+        let output = if let Some(mut delay) = delay {
+            delay.update(input, current, time.delta())
+        } else if let Some(mut latch) = latch {
+            let output = latch.update(input, current);
+            if output != current {
+                world_state.set(entity_iid, PersistentState::Active(output));
+            }
+            output
+        } else if let Some(mut timer) = timer {
+            timer.update(input, time.delta())
+        } else {
+            input
+        };
+
+        if output != current
+            && let Ok(mut status) = statuses.get_mut(entity)
+        {
+            status.0 = output;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn combine_inputs() {
+        let cases = [
+            (LogicOperation::And, [false, false, true, false]),
+            (LogicOperation::Or, [false, true, true, true]),
+            (LogicOperation::Xor, [false, true, false, true]),
+            (LogicOperation::Not, [true, false, false, false]),
+        ];
+        let inputs = [[false, false], [true, false], [true, true], [false, true]];
+
+        for (operation, expected) in cases {
+            for (input, output) in inputs.iter().zip(expected) {
+                assert_eq!(
+                    operation.combine(input),
+                    output,
+                    "{operation:?} of {input:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn combine_without_inputs() {
+        assert!(!LogicOperation::And.combine(&[]));
+        assert!(!LogicOperation::Or.combine(&[]));
+        assert!(!LogicOperation::Xor.combine(&[]));
+        assert!(LogicOperation::Not.combine(&[]));
+    }
+
+    #[test]
+    fn operation_from_name() {
+        assert_eq!(LogicOperation::from_name("And"), LogicOperation::And);
+        assert_eq!(LogicOperation::from_name("Xor"), LogicOperation::Xor);
+        assert_eq!(LogicOperation::from_name("Not"), LogicOperation::Not);
+        assert_eq!(LogicOperation::from_name("Unknown"), LogicOperation::Or);
+    }
+
+    #[test]
+    fn latch_toggles_on_rising_edges() {
+        let mut latch = LogicLatch::default();
+        let mut output = latch.update(false, false);
+        assert!(!output);
+
+        let expected = [(true, true), (true, true), (false, true), (true, false)];
+        for (input, expected_output) in expected {
+            output = latch.update(input, output);
+            assert_eq!(output, expected_output);
+        }
+    }
+
+    #[test]
+    fn latch_keeps_state_when_spawned_with_active_input() {
+        let mut latch = LogicLatch::default();
+        assert!(latch.update(true, true));
+        assert!(!latch.update(true, false));
+    }
+
+    #[test]
+    fn delay_follows_input_after_delay() {
+        let mut delay = LogicDelay {
+            delay: 0.25,
+            ..default()
+        };
+
+        let mut output = delay.update(true, false, STEP);
+        assert!(!output);
+        output = delay.update(true, output, STEP);
+        assert!(!output);
+        output = delay.update(false, output, STEP);
+        assert!(output, "the rising edge is emitted after the delay");
+        output = delay.update(false, output, STEP);
+        assert!(output);
+        output = delay.update(false, output, STEP);
+        assert!(!output, "the falling edge is emitted after the delay");
+    }
+
+    #[test]
+    fn timer_stays_active_for_duration() {
+        let mut timer = LogicTimer {
+            timer: Timer::from_seconds(0.25, TimerMode::Once),
+            last_input: false,
+        };
+        timer.timer.tick(timer.timer.duration());
+
+        assert!(!timer.update(false, STEP));
+        assert!(timer.update(true, STEP));
+        assert!(timer.update(false, STEP));
+        assert!(!timer.update(false, STEP));
+        assert!(
+            timer.update(true, STEP),
+            "a new rising edge restarts the timer"
+        );
+    }
+}
@@ -1,9 +1,31 @@
 use bevy::prelude::*;
 
+pub mod activation;
 pub mod button;
 pub mod checkpoint;
+pub mod crumbling_platform;
 pub mod door;
+pub mod entrance;
+pub mod force_zone;
+pub mod jump_orb;
+pub mod lever;
+pub mod liquid;
+pub mod logic;
+pub mod spring;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((button::plugin, door::plugin, checkpoint::plugin));
+    app.add_plugins((
+        activation::plugin,
+        button::plugin,
+        door::plugin,
+        entrance::plugin,
+        checkpoint::plugin,
+        crumbling_platform::plugin,
+        force_zone::plugin,
+        jump_orb::plugin,
+        lever::plugin,
+        liquid::plugin,
+        logic::plugin,
+        spring::plugin,
+    ));
 }
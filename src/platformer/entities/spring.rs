@@ -0,0 +1,219 @@
+//! Springs that launch the player without consuming a jump
+
+use bevy::prelude::*;
+
+use avian2d::{math::*, prelude::*};
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_events::SpringEvent;
+use sf_ui::prelude::Screen;
+
+use crate::{
+    GameLayer, PausableSystems,
+    assets::collections::LevelAssets,
+    audio::sound_effect,
+    platformer::level::CurrentLevel,
+    player::{
+        death::Dead,
+        movement::{CoyoteTimer, MovementModifierSystems},
+        physics::{CharacterController, ExternalVelocity, Grounded},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<SpringBundle>("Spring");
+    app.add_systems(
+        FixedUpdate,
+        (detect_spring_contact, update_spring)
+            .chain()
+            .in_set(MovementModifierSystems::Springs)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        (update_sprite_spring, play_spring_sound).run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Time during which the spring stays compressed after a launch.
+const SPRING_COMPRESSED_SECS: f32 = 0.2;
+
+/// Spring that launches the player in its direction.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Spring {
+    /// Speed given to the player along the spring direction.
+    impulse: Scalar,
+    /// Direction in which the player is launched.
+    direction: Dir2,
+    /// Timer of the compression, the spring is extended when finished.
+    compressed: Timer,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        let mut compressed = Timer::from_seconds(SPRING_COMPRESSED_SECS, TimerMode::Once);
+        compressed.tick(compressed.duration());
+
+        Self {
+            impulse: 0.0,
+            direction: Dir2::Y,
+            compressed,
+        }
+    }
+}
+
+impl Spring {
+    fn from_field(entity_instance: &EntityInstance) -> Self {
+        let impulse = *entity_instance
+            .get_float_field("impulse")
+            .expect("Expected spring to have impulse field");
+
+        let direction = match entity_instance
+            .get_enum_field("direction")
+            .expect("Expected spring to have direction field")
+            .as_str()
+        {
+            "Down" => Dir2::NEG_Y,
+            "Left" => Dir2::NEG_X,
+            "Right" => Dir2::X,
+            _ => Dir2::Y,
+        };
+
+        Self {
+            impulse,
+            direction,
+            ..default()
+        }
+    }
+
+    fn is_compressed(&self) -> bool {
+        !self.compressed.is_finished()
+    }
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct SpringBundle {
+    #[with(Spring::from_field)]
+    spring: Spring,
+
+    #[sprite_sheet]
+    sprite: Sprite,
+
+    // Physics
+    body: RigidBody,
+    collider: Collider,
+    sensor: Sensor,
+    collision_layer: CollisionLayers,
+    collision_event: CollisionEventsEnabled,
+    colliding_entities: CollidingEntities,
+}
+
+impl Default for SpringBundle {
+    fn default() -> Self {
+        Self {
+            spring: Spring::default(),
+            sprite: Sprite::default(),
+            body: RigidBody::Static,
+            collider: Collider::rectangle(6.0, 4.0),
+            sensor: Sensor,
+            collision_layer: CollisionLayers::new(GameLayer::Sensor, [GameLayer::Player]),
+            collision_event: CollisionEventsEnabled,
+            colliding_entities: CollidingEntities::default(),
+        }
+    }
+}
+
+/// Launch the player when it touches the spring from the side it is facing.
+fn detect_spring_contact(
+    mut commands: Commands,
+    mut spring_event: MessageWriter<SpringEvent>,
+    current_level: Res<CurrentLevel>,
+    springs: Query<(&CollidingEntities, &GlobalTransform, &mut Spring)>,
+    player: Single<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut LinearVelocity,
+            &mut ExternalVelocity,
+            &mut CoyoteTimer,
+        ),
+        (With<CharacterController>, Without<Dead>),
+    >,
+) {
+    let (
+        player_entity,
+        player_transform,
+        mut linear_velocity,
+        mut external_velocity,
+        mut coyote_timer,
+    ) = player.into_inner();
+
+    for (colliding_entities, spring_transform, mut spring) in springs {
+        if spring.is_compressed() || !colliding_entities.0.contains(&player_entity) {
+            continue;
+        }
+
+        let offset =
+            player_transform.translation().truncate() - spring_transform.translation().truncate();
+        let is_facing = offset.dot(*spring.direction) > 0.0;
+        let velocity = Vector::new(external_velocity.0, linear_velocity.y);
+        let is_moving_away = velocity.dot(*spring.direction) > 0.0;
+
+        if !is_facing || is_moving_away {
+            continue;
+        }
+
+        // Only replace the velocity along the spring. The horizontal part goes through
+        // the external velocity as the movement overwrites the horizontal velocity.
+        let launch = spring.direction * spring.impulse;
+        if launch.x != 0.0 {
+            external_velocity.0 = launch.x;
+        }
+        if launch.y != 0.0 {
+            linear_velocity.y = launch.y;
+        }
+
+        // The launch is not a jump: the jump amount is left untouched,
+        // but the player can't use the coyote time to jump again.
+        coyote_timer.consume();
+        commands.entity(player_entity).remove::<Grounded>();
+
+        spring.compressed.reset();
+        spring_event.write(SpringEvent {
+            player: player_entity,
+            position: player_transform.translation().truncate(),
+            level: current_level.iid.clone(),
+        });
+    }
+}
+
+fn play_spring_sound(
+    mut commands: Commands,
+    mut spring_event: MessageReader<SpringEvent>,
+    level_assets: Res<LevelAssets>,
+) {
+    for _ in spring_event.read() {
+        commands.spawn((
+            Name::new("Spring Sound"),
+            sound_effect(level_assets.spring_sound.clone()),
+        ));
+    }
+}
+
+fn update_spring(time: Res<Time>, springs: Query<&mut Spring>) {
+    for mut spring in springs {
+        if spring.is_compressed() {
+            spring.compressed.tick(time.delta());
+        }
+    }
+}
+
+fn update_sprite_spring(query: Query<(&mut Sprite, &Spring), Changed<Spring>>) {
+    for (mut sprite, spring) in query {
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.index = if spring.is_compressed() { 1 } else { 0 };
+        }
+    }
+}
@@ -3,25 +3,39 @@
 use bevy::prelude::*;
 use bevy::ui::Val::*;
 
+use sf_events::LevelEnterEvent;
 use sf_ui::prelude::Screen;
 
 use sf_ui::prelude::{UiAssets, UiTheme};
 
-use crate::player::{movement::JumpAmount, physics::CharacterController};
+use crate::{
+    platformer::level_settings::ActiveLevelSettings,
+    player::{movement::JumpAmount, physics::CharacterController},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<JumpCounter>();
+    app.register_type::<LevelBanner>();
     app.add_systems(OnEnter(Screen::Gameplay), spawn_hud);
     app.add_systems(
         Update,
-        update_jump_counter.run_if(in_state(Screen::Gameplay)),
+        (update_jump_counter, show_level_banner, update_level_banner)
+            .run_if(in_state(Screen::Gameplay)),
     );
 }
 
+const BANNER_FADE_SECS: f32 = 0.5;
+const BANNER_DURATION_SECS: f32 = 2.5;
+
 #[derive(Component, Reflect, Debug, Default, PartialEq)]
 #[reflect(Component)]
 pub struct JumpCounter;
 
+/// Name of the level shown when the player enters it.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct LevelBanner(Timer);
+
 fn spawn_hud(mut commands: Commands, ui_assets: Res<UiAssets>) {
     commands.spawn((
         Name::new("HUD"),
@@ -78,3 +92,79 @@ fn update_jump_counter(
 ) {
     jump_counter.0 = jump_amount.remaining.to_string();
 }
+
+/// Show the name of the entered level, once the level is spawned.
+fn show_level_banner(
+    mut commands: Commands,
+    mut level_enter_event: MessageReader<LevelEnterEvent>,
+    // Whether the entered level is visited for the first time, kept until its settings exist
+    mut pending_first_visit: Local<Option<bool>>,
+    level_settings: ActiveLevelSettings,
+    banners: Query<Entity, With<LevelBanner>>,
+) {
+    if let Some(event) = level_enter_event.read().last() {
+        *pending_first_visit = Some(event.first_visit);
+    }
+
+    let Some(first_visit) = *pending_first_visit else {
+        return;
+    };
+    let Some(settings) = level_settings.get() else {
+        return;
+    };
+    *pending_first_visit = None;
+
+    if !first_visit && !settings.always_show_name {
+        return;
+    }
+
+    for entity in banners {
+        commands.entity(entity).despawn();
+    }
+
+    commands.spawn((
+        Name::new("Level Banner"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            top: Percent(20.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        GlobalZIndex(1),
+        DespawnOnExit(Screen::Gameplay),
+        LevelBanner(Timer::from_seconds(BANNER_DURATION_SECS, TimerMode::Once)),
+        children![(
+            Name::new("Level Name"),
+            Text(settings.display_name.clone()),
+            UiTheme::PIXEL_ART,
+            TextFont::from_font_size(26.0),
+            TextColor(Color::WHITE.with_alpha(0.0)),
+        )],
+    ));
+}
+
+/// Fade the banner in and out, then remove it.
+fn update_level_banner(
+    mut commands: Commands,
+    time: Res<Time>,
+    banners: Query<(Entity, &mut LevelBanner, &Children)>,
+    mut texts: Query<&mut TextColor>,
+) {
+    for (entity, mut banner, children) in banners {
+        banner.0.tick(time.delta());
+        if banner.0.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let elapsed = banner.0.elapsed_secs();
+        let remaining = banner.0.remaining_secs();
+        let alpha = (elapsed.min(remaining) / BANNER_FADE_SECS).min(1.0);
+
+        let mut text_colors = texts.iter_many_mut(children);
+        while let Some(mut text_color) = text_colors.fetch_next() {
+            text_color.0.set_alpha(alpha);
+        }
+    }
+}
@@ -3,52 +3,127 @@
 use bevy::prelude::*;
 
 use avian2d::prelude::*;
-use bevy_ecs_ldtk::prelude::*;
+use bevy_ecs_ldtk::{
+    ldtk::{Level, Type},
+    prelude::*,
+};
 
-use sf_events::DeathEvent;
+use sf_events::{DeathCause, DeathEvent};
 use sf_ui::prelude::Screen;
 
 use crate::{
-    GameLayer,
+    GameLayer, PausableSystems,
     assets::collections::{LevelAssets, PlayerAssets},
     audio::music,
-    camera::{LEVEL_HEIGHT, LEVEL_WIDTH, MainCamera},
+    camera::{LEVEL_HEIGHT, LEVEL_WIDTH},
+    platformer::{
+        level_settings::LevelSettings,
+        walls::{merge_cells, wall_cells},
+    },
     player::{
         animation::{CharacterSpriteBundle, PlayerAnimationState},
-        movement::MovementBundle,
+        death::PendingPlacement,
+        movement::{MovementBundle, MovementSystems},
         physics::{CharacterController, CharacterControllerBundle, Grounded},
     },
 };
 
 pub(super) fn plugin(app: &mut App) {
     // LDTK
-    app.insert_resource(LevelSelection::Uid(0));
-    app.register_ldtk_int_cell::<WallBundle>(1);
+    app.init_resource::<CurrentLevel>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_current_level);
     app.add_systems(
         Update,
-        (update_level_selection, restart_level).run_if(in_state(Screen::Gameplay)),
+        (spawn_wall_colliders, restart_level).run_if(in_state(Screen::Gameplay)),
+    );
+    // After the entrances, which select the level before the player is placed in it
+    app.add_systems(
+        FixedUpdate,
+        update_current_level
+            .after(MovementSystems)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(not(resource_exists::<PendingPlacement>))),
     );
 }
 
+/// The level the player is in.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct CurrentLevel {
+    pub iid: Option<LevelIid>,
+    /// World bounds of the level.
+    pub bounds: Rect,
+}
+
 #[derive(Default, Component, Reflect, Debug)]
 #[reflect(Component)]
 pub struct Wall;
 
-#[derive(Bundle, LdtkIntCell)]
-struct WallBundle {
-    wall: Wall,
-    collider: Collider,
-    collision_layers: CollisionLayers,
-    body: RigidBody,
-}
+/// Spawn one collider per rectangle of wall tiles instead of one per tile.
+///
+/// The wall tiles are read from the LDtk project, no entity is spawned for each of them.
+/// Merging the tiles also removes the seams on which the character controller can catch.
+fn spawn_wall_colliders(
+    mut commands: Commands,
+    layers: Query<(Entity, &LayerMetadata, &ChildOf), Added<LayerMetadata>>,
+    levels: Query<&LevelIid>,
+    ldtk_project_handle: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    if layers.is_empty() {
+        return;
+    }
+
+    let ldtk_project = ldtk_project_assets
+        .get(*ldtk_project_handle)
+        .expect("Project should be loaded if level is spawned.");
+
+    for (layer_entity, layer, ChildOf(level_entity)) in &layers {
+        if layer.layer_instance_type != Type::IntGrid {
+            continue;
+        }
+
+        let Some(layer_instance) = levels
+            .get(*level_entity)
+            .ok()
+            .and_then(|level_iid| ldtk_project.get_raw_level_by_iid(&level_iid.to_string()))
+            .and_then(|level| {
+                level
+                    .layer_instances
+                    .iter()
+                    .flatten()
+                    .find(|layer_instance| layer_instance.iid == layer.iid)
+            })
+        else {
+            continue;
+        };
+
+        let cells = wall_cells(layer_instance);
+        let rects = merge_cells(&cells, layer.c_wid, layer.c_hei);
+        debug!(
+            "Merged {} wall tiles of layer {} into {} colliders",
+            cells.len(),
+            layer.identifier,
+            rects.len()
+        );
 
-impl Default for WallBundle {
-    fn default() -> Self {
-        Self {
-            wall: Wall,
-            collider: Collider::rectangle(8.0, 8.0),
-            collision_layers: CollisionLayers::new(GameLayer::Ground, [GameLayer::Player]),
-            body: RigidBody::Static,
+        let grid_size = layer.grid_size as f32;
+        for rect in rects {
+            let size = Vec2::new(
+                (rect.right - rect.left + 1) as f32,
+                (rect.top - rect.bottom + 1) as f32,
+            ) * grid_size;
+            let position = Vec2::new(rect.left as f32, rect.bottom as f32) * grid_size + size / 2.0;
+
+            commands.spawn((
+                Name::new("Wall Collider"),
+                Wall,
+                Collider::rectangle(size.x, size.y),
+                CollisionLayers::new(GameLayer::Ground, [GameLayer::Player]),
+                RigidBody::Static,
+                Transform::from_translation(position.extend(0.0)),
+                ChildOf(layer_entity),
+            ));
         }
     }
 }
@@ -85,11 +160,24 @@ pub fn spawn_level(
     ));
 }
 
-fn update_level_selection(
-    levels: Query<(&LevelIid, &Transform), (Without<CharacterController>, Without<MainCamera>)>,
-    player: Single<&Transform, (With<CharacterController>, Without<MainCamera>)>,
-    mut camera: Single<&mut Transform, With<MainCamera>>,
-    mut level_selection: ResMut<LevelSelection>,
+/// Bounds of the level in the world, read from the LDtk project.
+pub fn level_world_bounds(level: &Level) -> Rect {
+    let min = Vec2::new(level.world_x as f32, -(level.world_y + level.px_hei) as f32);
+    Rect::from_corners(
+        min,
+        min + Vec2::new(level.px_wid as f32, level.px_hei as f32),
+    )
+}
+
+fn reset_current_level(mut current_level: ResMut<CurrentLevel>) {
+    *current_level = CurrentLevel::default();
+}
+
+/// Find the level the player is in, once its settings are read.
+fn update_current_level(
+    levels: Query<(&LevelIid, &Transform), (With<LevelSettings>, Without<CharacterController>)>,
+    player: Single<&Transform, With<CharacterController>>,
+    mut current_level: ResMut<CurrentLevel>,
     ldtk_project_handle: Single<&LdtkProjectHandle>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
 ) {
@@ -108,11 +196,15 @@ fn update_level_selection(
                 + Vec2::new(level.px_wid as f32, level.px_hei as f32),
         };
 
-        if !level_selection.is_match(&LevelIndices::default(), level)
-            && level_bounds.contains(player.translation.truncate())
-        {
-            *level_selection = LevelSelection::iid(level.iid.clone());
-            camera.translation = level_bounds.center().extend(camera.translation.z);
+        if !level_bounds.contains(player.translation.truncate()) {
+            continue;
+        }
+
+        if current_level.iid.as_ref() != Some(level_iid) {
+            *current_level = CurrentLevel {
+                iid: Some(level_iid.clone()),
+                bounds: level_bounds,
+            };
         }
     }
 }
@@ -120,9 +212,16 @@ fn update_level_selection(
 fn restart_level(
     mut death_event: MessageWriter<DeathEvent>,
     input: Res<ButtonInput<KeyCode>>,
-    player_grounded: Single<Has<Grounded>, With<CharacterController>>,
+    current_level: Res<CurrentLevel>,
+    player: Single<(Entity, &Transform, Has<Grounded>), With<CharacterController>>,
 ) {
-    if input.just_pressed(KeyCode::KeyR) && *player_grounded {
-        death_event.write(DeathEvent);
+    let (entity, transform, is_grounded) = *player;
+    if input.just_pressed(KeyCode::KeyR) && is_grounded {
+        death_event.write(DeathEvent {
+            player: entity,
+            position: transform.translation.truncate(),
+            level: current_level.iid.clone(),
+            cause: DeathCause::Restart,
+        });
     }
 }
@@ -0,0 +1,196 @@
+//! Properties of the levels read from their LDtk fields
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use bevy_ecs_ldtk::{ldtk::Level, prelude::*};
+
+use sf_ui::prelude::Screen;
+
+use crate::{
+    assets::collections::LevelAssets,
+    audio::{Music, music},
+    camera::MainCamera,
+    platformer::level::CurrentLevel,
+    player::physics::{CharacterController, GravityController},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            process_level_settings,
+            (
+                apply_level_gravity,
+                apply_level_background,
+                apply_level_music,
+            )
+                .run_if(
+                    resource_changed::<CurrentLevel>.or(any_match_filter::<Added<LevelSettings>>),
+                ),
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_level_background);
+}
+
+/// Settings of a level, all the fields are optional in LDtk.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct LevelSettings {
+    /// Name shown to the player, the LDtk identifier if missing.
+    pub display_name: String,
+    /// Show the name each time the player enters the level, not only the first time.
+    pub always_show_name: bool,
+    /// Path of the music played in the level, the gameplay music if `None`.
+    pub music: Option<String>,
+    pub gravity_multiplier: f32,
+    pub background_color: Color,
+    /// Whether the body of the player stays in the level after a death.
+    pub persistent_corpses: bool,
+}
+
+impl LevelSettings {
+    pub fn from_level(level: &Level) -> Self {
+        let display_name = level
+            .get_maybe_string_field("name")
+            .ok()
+            .and_then(Clone::clone)
+            .unwrap_or_else(|| level.identifier.clone());
+
+        let always_show_name = level
+            .get_bool_field("always_show_name")
+            .ok()
+            .copied()
+            .unwrap_or(false);
+
+        let music = level
+            .get_maybe_string_field("music")
+            .ok()
+            .and_then(Clone::clone);
+
+        let gravity_multiplier = level
+            .get_maybe_float_field("gravity_multiplier")
+            .ok()
+            .and_then(|multiplier| *multiplier)
+            .unwrap_or(1.0);
+
+        let background_color = level
+            .get_color_field("background_color")
+            .ok()
+            .copied()
+            .unwrap_or(level.bg_color);
+
+        let persistent_corpses = level
+            .get_bool_field("persistent_corpses")
+            .ok()
+            .copied()
+            .unwrap_or(true);
+
+        Self {
+            display_name,
+            always_show_name,
+            music,
+            gravity_multiplier,
+            background_color,
+            persistent_corpses,
+        }
+    }
+}
+
+/// Settings of the level the player is in.
+#[derive(SystemParam)]
+pub struct ActiveLevelSettings<'w, 's> {
+    current_level: Res<'w, CurrentLevel>,
+    levels: Query<'w, 's, (&'static LevelIid, &'static LevelSettings)>,
+}
+
+impl ActiveLevelSettings<'_, '_> {
+    pub fn get(&self) -> Option<&LevelSettings> {
+        let current_iid = self.current_level.iid.as_ref()?;
+
+        self.levels
+            .iter()
+            .find(|(level_iid, _)| *level_iid == current_iid)
+            .map(|(_, settings)| settings)
+    }
+}
+
+fn process_level_settings(
+    mut commands: Commands,
+    new_levels: Query<(Entity, &LevelIid), Added<LevelIid>>,
+    ldtk_project_handle: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    if new_levels.is_empty() {
+        return;
+    }
+
+    let ldtk_project = ldtk_project_assets
+        .get(*ldtk_project_handle)
+        .expect("Project should be loaded if level is spawned.");
+
+    for (entity, level_iid) in &new_levels {
+        let level = ldtk_project
+            .get_raw_level_by_iid(&level_iid.to_string())
+            .expect("Spawned level should exist in LDtk project.");
+
+        commands
+            .entity(entity)
+            .insert(LevelSettings::from_level(level));
+    }
+}
+
+fn apply_level_gravity(
+    settings: ActiveLevelSettings,
+    mut gravity: Single<&mut GravityController, With<CharacterController>>,
+) {
+    if let Some(settings) = settings.get() {
+        gravity.multiplier = settings.gravity_multiplier;
+    }
+}
+
+fn apply_level_background(
+    settings: ActiveLevelSettings,
+    mut camera: Single<&mut Camera, With<MainCamera>>,
+) {
+    if let Some(settings) = settings.get() {
+        camera.clear_color = ClearColorConfig::Custom(settings.background_color);
+    }
+}
+
+fn reset_level_background(mut camera: Single<&mut Camera, With<MainCamera>>) {
+    camera.clear_color = ClearColorConfig::Default;
+}
+
+/// Switch the music when the track of the new level is different.
+fn apply_level_music(
+    mut commands: Commands,
+    settings: ActiveLevelSettings,
+    asset_server: Res<AssetServer>,
+    level_assets: Res<LevelAssets>,
+    musics: Query<(Entity, &AudioPlayer), With<Music>>,
+) {
+    let Some(settings) = settings.get() else {
+        return;
+    };
+
+    let track = match &settings.music {
+        Some(path) => asset_server.load(path),
+        None => level_assets.music.clone(),
+    };
+
+    if musics.iter().any(|(_, player)| player.0 == track) {
+        return;
+    }
+
+    for (entity, _) in &musics {
+        commands.entity(entity).despawn();
+    }
+
+    commands.spawn((
+        Name::new("Gameplay Music"),
+        music(track),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
@@ -0,0 +1,188 @@
+//! World map drawn from the layout of the LDtk levels the player visited
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+
+use avian2d::prelude::{Physics, PhysicsTime};
+use bevy_ecs_ldtk::{ldtk::Level, prelude::*};
+use leafwing_input_manager::prelude::*;
+
+use sf_ui::prelude::{Menu, Screen, widget};
+
+use crate::{
+    Action, Pause,
+    platformer::{level::CurrentLevel, world_state::WorldState},
+    player::physics::CharacterController,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Map), spawn_map);
+    app.add_systems(
+        Update,
+        (
+            open_map.run_if(in_state(Menu::None).and(in_state(Pause(false)))),
+            close_map.run_if(in_state(Menu::Map)),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Map).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+/// Size of the area in which the map is drawn.
+const MAP_SIZE: Vec2 = Vec2::new(280.0, 140.0);
+const MARKER_SIZE: f32 = 3.0;
+
+const MAP_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.9);
+const ROOM_COLOR: Color = Color::srgb(0.239, 0.286, 0.600);
+const CURRENT_ROOM_COLOR: Color = Color::srgb(0.384, 0.600, 0.820);
+const ROOM_BORDER_COLOR: Color = Color::srgb(0.925, 0.925, 0.925);
+const CHECKPOINT_COLOR: Color = Color::srgb(0.87, 0.83, 0.41);
+const DOOR_COLOR: Color = Color::srgb(0.80, 0.35, 0.30);
+
+fn open_map(
+    player: Single<&ActionState<Action>, With<CharacterController>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut physic_time: ResMut<Time<Physics>>,
+) {
+    if player.just_pressed(&Action::Map) {
+        physic_time.pause();
+        next_pause.set(Pause(true));
+        next_menu.set(Menu::Map);
+    }
+}
+
+fn close_map(
+    player: Single<&ActionState<Action>, With<CharacterController>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if player.just_pressed(&Action::Map) {
+        next_menu.set(Menu::None);
+    }
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
+
+fn spawn_map(
+    mut commands: Commands,
+    world_state: Res<WorldState>,
+    current_level: Res<CurrentLevel>,
+    ldtk_project_handle: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    let ldtk_project = ldtk_project_assets
+        .get(*ldtk_project_handle)
+        .expect("Project should be loaded during gameplay.");
+
+    // Only the rooms the player entered are revealed
+    let levels: Vec<&Level> = ldtk_project
+        .iter_raw_levels()
+        .filter(|level| world_state.is_level_discovered(&LevelIid::new(level.iid.clone())))
+        .collect();
+
+    let world_bounds = levels
+        .iter()
+        .map(|level| level_rect(level))
+        .reduce(|bounds, rect| bounds.union(rect))
+        .unwrap_or_default();
+    let scale = (MAP_SIZE / world_bounds.size().max(Vec2::ONE)).min_element();
+    let to_map = |position: Vec2| (position - world_bounds.min) * scale;
+
+    let map = commands
+        .spawn((
+            widget::ui_root("Map"),
+            GlobalZIndex(2),
+            BackgroundColor(MAP_BACKGROUND),
+            DespawnOnExit(Menu::Map),
+            children![widget::header("Map")],
+        ))
+        .id();
+
+    let area = commands
+        .spawn((
+            Name::new("Map Area"),
+            Node {
+                width: Px(MAP_SIZE.x),
+                height: Px(MAP_SIZE.y),
+                ..default()
+            },
+            ChildOf(map),
+        ))
+        .id();
+
+    for level in &levels {
+        let rect = level_rect(level);
+        let is_current = current_level
+            .iid
+            .as_ref()
+            .is_some_and(|level_iid| level_iid.as_str() == level.iid);
+
+        commands.spawn((
+            Name::new(format!("Room {}", level.identifier)),
+            Node {
+                border: UiRect::all(Px(1.0)),
+                ..map_node(to_map(rect.min), rect.size() * scale)
+            },
+            BorderColor::all(ROOM_BORDER_COLOR),
+            BackgroundColor(if is_current {
+                CURRENT_ROOM_COLOR
+            } else {
+                ROOM_COLOR
+            }),
+            ChildOf(area),
+        ));
+
+        for entity_instance in level
+            .layer_instances
+            .iter()
+            .flatten()
+            .flat_map(|layer| &layer.entity_instances)
+        {
+            let color = match entity_instance.identifier.as_str() {
+                "Checkpoint"
+                    if world_state.is_discovered(&EntityIid::new(entity_instance.iid.clone())) =>
+                {
+                    CHECKPOINT_COLOR
+                }
+                "Door" => DOOR_COLOR,
+                _ => continue,
+            };
+
+            let position = rect.min + entity_instance.px.as_vec2();
+            commands.spawn((
+                Name::new(format!("{} Marker", entity_instance.identifier)),
+                map_node(
+                    to_map(position) - MARKER_SIZE / 2.0,
+                    Vec2::splat(MARKER_SIZE),
+                ),
+                BackgroundColor(color),
+                GlobalZIndex(3),
+                ChildOf(area),
+            ));
+        }
+    }
+}
+
+/// Rectangle of the level in the LDtk world, y pointing down like the UI.
+fn level_rect(level: &Level) -> Rect {
+    let min = Vec2::new(level.world_x as f32, level.world_y as f32);
+    Rect::from_corners(
+        min,
+        min + Vec2::new(level.px_wid as f32, level.px_hei as f32),
+    )
+}
+
+fn map_node(position: Vec2, size: Vec2) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        left: Px(position.x),
+        top: Px(position.y),
+        width: Px(size.x),
+        height: Px(size.y),
+        ..default()
+    }
+}
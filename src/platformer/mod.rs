@@ -5,7 +5,22 @@ use bevy::prelude::*;
 pub mod entities;
 mod hud;
 pub mod level;
+pub mod level_settings;
+mod map;
+pub mod streaming;
+pub mod transition;
+mod walls;
+pub mod world_state;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((level::plugin, hud::plugin, entities::plugin));
+    app.add_plugins((
+        level::plugin,
+        level_settings::plugin,
+        hud::plugin,
+        entities::plugin,
+        map::plugin,
+        streaming::plugin,
+        transition::plugin,
+        world_state::plugin,
+    ));
 }
@@ -0,0 +1,98 @@
+//! Keep only the levels around the player spawned
+
+use std::collections::VecDeque;
+
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_ui::prelude::Screen;
+
+use crate::platformer::level::CurrentLevel;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LevelStreaming>();
+    app.add_systems(
+        Update,
+        update_level_set.run_if(
+            in_state(Screen::Gameplay).and(
+                resource_changed::<CurrentLevel>
+                    .or(resource_changed::<LevelStreaming>)
+                    .or(any_match_filter::<Added<LevelSet>>),
+            ),
+        ),
+    );
+}
+
+/// Uid of the level spawned at the start of a run.
+const START_LEVEL_UID: i32 = 0;
+
+/// Streaming policy of the levels.
+///
+/// The entities of a despawned level are restored from the
+/// [`WorldState`](crate::platformer::world_state::WorldState) when it is spawned again.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct LevelStreaming {
+    /// How many neighbours away from the current level a level stays spawned.
+    ///
+    /// At least the direct neighbours are kept, the current level is only updated
+    /// from the spawned levels.
+    pub neighbour_depth: usize,
+}
+
+impl Default for LevelStreaming {
+    fn default() -> Self {
+        Self { neighbour_depth: 1 }
+    }
+}
+
+/// Spawn the current level and its neighbours, the levels further away are despawned.
+fn update_level_set(
+    streaming: Res<LevelStreaming>,
+    current_level: Res<CurrentLevel>,
+    world: Single<(&LdtkProjectHandle, &mut LevelSet)>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    let (ldtk_project_handle, mut level_set) = world.into_inner();
+    let Some(ldtk_project) = ldtk_project_assets.get(ldtk_project_handle) else {
+        return;
+    };
+
+    let center = match &current_level.iid {
+        Some(level_iid) => level_iid.clone(),
+        None => match ldtk_project
+            .iter_raw_levels()
+            .find(|level| level.uid == START_LEVEL_UID)
+        {
+            Some(level) => LevelIid::new(level.iid.clone()),
+            None => return,
+        },
+    };
+
+    // Breadth first walk of the neighbours up to the depth of the policy
+    let neighbour_depth = streaming.neighbour_depth.max(1);
+    let mut iids = HashSet::from([center.clone()]);
+    let mut queue = VecDeque::from([(center, 0)]);
+    while let Some((level_iid, depth)) = queue.pop_front() {
+        if depth >= neighbour_depth {
+            continue;
+        }
+
+        let Some(level) = ldtk_project.get_raw_level_by_iid(&level_iid.to_string()) else {
+            continue;
+        };
+
+        for neighbour in &level.neighbours {
+            let neighbour_iid = LevelIid::new(neighbour.level_iid.clone());
+            if iids.insert(neighbour_iid.clone()) {
+                queue.push_back((neighbour_iid, depth + 1));
+            }
+        }
+    }
+
+    let new_level_set = LevelSet::from_iids(iids);
+    if *level_set != new_level_set {
+        *level_set = new_level_set;
+    }
+}
@@ -0,0 +1,218 @@
+//! Animated transitions when the player goes from a level to another
+
+use bevy::{prelude::*, ui::Val::*};
+
+use avian2d::prelude::{Physics, PhysicsTime};
+use bevy_ecs_ldtk::{ldtk::Level, prelude::*};
+
+use sf_ui::prelude::Screen;
+
+use crate::{
+    PausableSystems, Pause,
+    camera::{MainCamera, clamp_to_level},
+    platformer::level::CurrentLevel,
+    player::{
+        death::{PendingPlacement, RespawnSystems},
+        physics::CharacterController,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    // The player can't move during a transition
+    app.configure_sets(
+        Update,
+        PausableSystems.run_if(not(resource_exists::<RoomTransition>)),
+    );
+    app.configure_sets(
+        FixedUpdate,
+        PausableSystems.run_if(not(resource_exists::<RoomTransition>)),
+    );
+
+    app.add_systems(
+        Update,
+        (start_room_transition, update_room_transition)
+            .chain()
+            .after(RespawnSystems)
+            .run_if(in_state(Screen::Gameplay).and(in_state(Pause(false)))),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), clear_room_transition);
+}
+
+const PAN_DURATION_SECS: f32 = 0.5;
+const FADE_DURATION_SECS: f32 = 0.6;
+
+/// How the camera goes to the new level, set with the `transition` level field.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionStyle {
+    /// The camera jumps to the new level.
+    Cut,
+    /// The camera slides to the new level.
+    #[default]
+    Pan,
+    /// The screen fades to black and back on the new level.
+    Fade,
+}
+
+impl TransitionStyle {
+    /// Read the optional `transition` enum field of a level, [`TransitionStyle::Pan`] if missing.
+    pub fn from_level(level: &Level) -> Self {
+        match level
+            .get_maybe_enum_field("transition")
+            .ok()
+            .and_then(|name| name.as_deref())
+        {
+            Some("Cut") => Self::Cut,
+            Some("Fade") => Self::Fade,
+            _ => Self::Pan,
+        }
+    }
+}
+
+/// Transition in progress, the physics and the player inputs are frozen while it exists.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct RoomTransition {
+    style: TransitionStyle,
+    timer: Timer,
+    /// Camera position in the previous level.
+    from: Vec2,
+    /// Camera position in the new level.
+    to: Vec2,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct TransitionOverlay;
+
+fn start_room_transition(
+    mut commands: Commands,
+    mut previous_level: Local<Option<LevelIid>>,
+    mut physic_time: ResMut<Time<Physics>>,
+    current_level: Res<CurrentLevel>,
+    pending_placement: Option<Res<PendingPlacement>>,
+    player: Single<&Transform, (With<CharacterController>, Without<MainCamera>)>,
+    camera: Single<&Transform, With<MainCamera>>,
+    ldtk_project_handle: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    if !current_level.is_changed() {
+        return;
+    }
+
+    // The first level of the run is shown directly
+    let Some(level_iid) = current_level.iid.clone() else {
+        *previous_level = None;
+        return;
+    };
+    let Some(previous_iid) = previous_level.replace(level_iid.clone()) else {
+        return;
+    };
+
+    // The respawn already moved the camera to the new level
+    if pending_placement
+        .as_ref()
+        .is_some_and(|pending_placement| pending_placement.is_respawn)
+    {
+        return;
+    }
+
+    let ldtk_project = ldtk_project_assets
+        .get(*ldtk_project_handle)
+        .expect("Project should be loaded if level is spawned.");
+    let level = ldtk_project
+        .get_raw_level_by_iid(&level_iid.to_string())
+        .expect("Current level should exist in LDtk project.");
+
+    // Sliding between levels that don't touch would cross the world
+    let is_neighbour = level
+        .neighbours
+        .iter()
+        .any(|neighbour| neighbour.level_iid == previous_iid.as_str());
+    let style = if is_neighbour {
+        TransitionStyle::from_level(level)
+    } else {
+        TransitionStyle::Fade
+    };
+    let duration = match style {
+        TransitionStyle::Cut => return,
+        TransitionStyle::Pan => PAN_DURATION_SECS,
+        TransitionStyle::Fade => FADE_DURATION_SECS,
+    };
+
+    if style == TransitionStyle::Fade {
+        commands.spawn((
+            Name::new("Transition Overlay"),
+            TransitionOverlay,
+            Node {
+                width: Percent(100.0),
+                height: Percent(100.0),
+                ..default()
+            },
+            GlobalZIndex(2),
+            BackgroundColor(Color::NONE),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+    }
+
+    physic_time.pause();
+    commands.insert_resource(RoomTransition {
+        style,
+        timer: Timer::from_seconds(duration, TimerMode::Once),
+        from: camera.translation.truncate(),
+        // The player may still wait for the level to be spawned
+        to: clamp_to_level(
+            pending_placement.map_or(player.translation.truncate(), |pending_placement| {
+                pending_placement.position
+            }),
+            current_level.bounds,
+        ),
+    });
+}
+
+fn update_room_transition(
+    mut commands: Commands,
+    time: Res<Time>,
+    transition: Option<ResMut<RoomTransition>>,
+    mut physic_time: ResMut<Time<Physics>>,
+    mut camera: Single<&mut Transform, With<MainCamera>>,
+    overlays: Query<(Entity, &mut BackgroundColor), With<TransitionOverlay>>,
+) {
+    let Some(mut transition) = transition else {
+        return;
+    };
+
+    // The pause menu may have resumed the physics in the middle of the transition
+    physic_time.pause();
+
+    let progress = transition.timer.tick(time.delta()).fraction();
+    let is_finished = transition.timer.is_finished();
+
+    let position = match transition.style {
+        TransitionStyle::Pan => transition.from.lerp(
+            transition.to,
+            EaseFunction::CubicInOut.sample_clamped(progress),
+        ),
+        // Move while the screen is black
+        _ if progress < 0.5 => transition.from,
+        _ => transition.to,
+    };
+    camera.translation = position.extend(camera.translation.z);
+
+    for (entity, mut background) in overlays {
+        if is_finished {
+            commands.entity(entity).despawn();
+        } else {
+            let alpha = 1.0 - (2.0 * progress - 1.0).abs();
+            background.0 = Color::BLACK.with_alpha(alpha);
+        }
+    }
+
+    if is_finished {
+        physic_time.unpause();
+        commands.remove_resource::<RoomTransition>();
+    }
+}
+
+fn clear_room_transition(mut commands: Commands) {
+    commands.remove_resource::<RoomTransition>();
+}
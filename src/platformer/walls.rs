@@ -0,0 +1,178 @@
+//! Merge the wall tiles of the IntGrid into rectangles
+//!
+//! Kept free of the ECS so the `wall_colliders` bench can include it.
+
+use bevy::platform::collections::{HashMap, HashSet};
+
+use bevy_ecs_ldtk::{ldtk::LayerInstance, prelude::GridCoords};
+
+/// Value of the wall tiles in the IntGrid.
+pub const WALL_VALUE: i32 = 1;
+
+/// A rectangle of wall tiles, in grid coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WallRect {
+    pub left: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub top: i32,
+}
+
+/// Grid coordinates of the wall tiles of an IntGrid layer.
+pub fn wall_cells(layer: &LayerInstance) -> HashSet<GridCoords> {
+    // The csv starts with the top row, the grid coordinates with the bottom one
+    layer
+        .int_grid_csv
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| **value == WALL_VALUE)
+        .map(|(index, _)| {
+            let index = index as i32;
+            GridCoords::new(index % layer.c_wid, layer.c_hei - 1 - index / layer.c_wid)
+        })
+        .collect()
+}
+
+/// Greedily merge the cells into rectangles.
+///
+/// Each row is split in horizontal runs, then a run extends the rectangle of the row
+/// below when it has the same span.
+pub fn merge_cells(cells: &HashSet<GridCoords>, width: i32, height: i32) -> Vec<WallRect> {
+    let mut rects: Vec<WallRect> = Vec::new();
+    // Rectangles that can still grow, keyed by their span
+    let mut open_rects: HashMap<(i32, i32), WallRect> = HashMap::new();
+
+    for y in 0..height {
+        let mut runs = Vec::new();
+        let mut run_start = None;
+        for x in 0..=width {
+            let is_wall = x < width && cells.contains(&GridCoords::new(x, y));
+            match (is_wall, run_start) {
+                (true, None) => run_start = Some(x),
+                (false, Some(start)) => {
+                    runs.push((start, x - 1));
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+
+        let mut next_open_rects = HashMap::new();
+        for (left, right) in runs {
+            let rect = match open_rects.remove(&(left, right)) {
+                Some(rect) => WallRect { top: y, ..rect },
+                None => WallRect {
+                    left,
+                    right,
+                    bottom: y,
+                    top: y,
+                },
+            };
+            next_open_rects.insert((left, right), rect);
+        }
+
+        // The rectangles not continued on this row are done
+        rects.extend(open_rects.into_values());
+        open_rects = next_open_rects;
+    }
+
+    rects.extend(open_rects.into_values());
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs_ldtk::ldtk::LdtkJson;
+
+    use super::*;
+
+    /// Cells of a grid drawn with `#` for the walls, the first row is the top one.
+    fn grid(rows: &[&str]) -> (HashSet<GridCoords>, i32, i32) {
+        let height = rows.len() as i32;
+        let width = rows[0].len() as i32;
+        let cells = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(x, _)| GridCoords::new(x as i32, height - 1 - row as i32))
+            })
+            .collect();
+
+        (cells, width, height)
+    }
+
+    /// Check that every wall is in exactly one rectangle and the rectangles only hold walls.
+    fn assert_covers(rects: &[WallRect], cells: &HashSet<GridCoords>) {
+        let mut covered = HashSet::new();
+        for rect in rects {
+            for x in rect.left..=rect.right {
+                for y in rect.bottom..=rect.top {
+                    let cell = GridCoords::new(x, y);
+                    assert!(cells.contains(&cell), "{rect:?} covers an empty cell");
+                    assert!(covered.insert(cell), "{cell:?} is in several rectangles");
+                }
+            }
+        }
+        assert_eq!(covered.len(), cells.len(), "some walls have no collider");
+    }
+
+    fn merge(rows: &[&str]) -> Vec<WallRect> {
+        let (cells, width, height) = grid(rows);
+        let rects = merge_cells(&cells, width, height);
+        assert_covers(&rects, &cells);
+        rects
+    }
+
+    #[test]
+    fn merge_full_block() {
+        assert_eq!(merge(&["###", "###", "###"]).len(), 1);
+    }
+
+    #[test]
+    fn merge_l_shape() {
+        assert_eq!(merge(&["#..", "#..", "###"]).len(), 2);
+    }
+
+    #[test]
+    fn merge_block_with_hole() {
+        assert_eq!(merge(&["###", "#.#", "###"]).len(), 4);
+    }
+
+    #[test]
+    fn merge_stacked_spans() {
+        assert_eq!(merge(&["..##..", ".####.", "######"]).len(), 3);
+        assert_eq!(merge(&["####", "####", "##..", "##.."]).len(), 2);
+    }
+
+    #[test]
+    fn merge_empty_grid() {
+        assert!(merge(&["...", "..."]).is_empty());
+    }
+
+    #[test]
+    fn merge_walls_of_real_level() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/world.ldtk");
+        let content = std::fs::read_to_string(path).expect("LDtk project exists");
+        let project: LdtkJson = serde_json::from_str(&content).expect("LDtk project is valid");
+        let level = project
+            .levels
+            .iter()
+            .find(|level| level.identifier == "Level_0")
+            .expect("Level_0 exists");
+        let layer = level
+            .layer_instances
+            .iter()
+            .flatten()
+            .find(|layer| layer.identifier == "Tiles")
+            .expect("Level_0 has a Tiles layer");
+
+        let cells = wall_cells(layer);
+        let rects = merge_cells(&cells, layer.c_wid, layer.c_hei);
+        assert_covers(&rects, &cells);
+        assert_eq!(cells.len(), 223);
+        assert_eq!(rects.len(), 9);
+    }
+}
@@ -0,0 +1,122 @@
+//! State of the world (LDtk entities, visited levels) that must survive the reload of the levels
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use bevy_ecs_ldtk::prelude::*;
+
+use sf_events::LevelEnterEvent;
+use sf_ui::prelude::Screen;
+
+use crate::{platformer::level::CurrentLevel, player::physics::CharacterController};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<WorldState>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_world_state);
+    app.add_systems(
+        Update,
+        record_level_discovery
+            .run_if(in_state(Screen::Gameplay).and(resource_changed::<CurrentLevel>)),
+    );
+}
+
+/// What the player did to an LDtk entity.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistentState {
+    /// The pickup was taken and should not be spawned again until the player respawns.
+    Collected,
+    /// Last state of a switch (lever, latching button, door).
+    Active(bool),
+    /// The player reached this entity at least once.
+    Discovered,
+}
+
+/// Persistent flags of the LDtk entities, keyed by their iid.
+///
+/// Entities are spawned fresh each time their level is loaded, so their processors read
+/// this resource to restore what the player already did.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct WorldState {
+    entities: HashMap<EntityIid, PersistentState>,
+    /// The checkpoint the player will respawn at.
+    last_checkpoint: Option<EntityIid>,
+    /// Levels the player already visited.
+    discovered_levels: HashSet<LevelIid>,
+}
+
+impl WorldState {
+    pub fn get(&self, entity_iid: &EntityIid) -> Option<PersistentState> {
+        self.entities.get(entity_iid).copied()
+    }
+
+    pub fn set(&mut self, entity_iid: &EntityIid, state: PersistentState) {
+        self.entities.insert(entity_iid.clone(), state);
+    }
+
+    pub fn is_collected(&self, entity_iid: &EntityIid) -> bool {
+        self.get(entity_iid) == Some(PersistentState::Collected)
+    }
+
+    /// Bring back every pickup, including the ones of the levels that are not spawned.
+    pub fn clear_collected(&mut self) {
+        self.entities
+            .retain(|_, state| *state != PersistentState::Collected);
+    }
+
+    /// Recorded state of a switch, `None` if the player never changed it.
+    pub fn is_active(&self, entity_iid: &EntityIid) -> Option<bool> {
+        match self.get(entity_iid) {
+            Some(PersistentState::Active(active)) => Some(active),
+            _ => None,
+        }
+    }
+
+    pub fn is_discovered(&self, entity_iid: &EntityIid) -> bool {
+        self.get(entity_iid) == Some(PersistentState::Discovered)
+    }
+
+    pub fn last_checkpoint(&self) -> Option<&EntityIid> {
+        self.last_checkpoint.as_ref()
+    }
+
+    /// Mark the checkpoint as discovered and use it for the next respawns.
+    pub fn activate_checkpoint(&mut self, entity_iid: &EntityIid) {
+        self.set(entity_iid, PersistentState::Discovered);
+        self.last_checkpoint = Some(entity_iid.clone());
+    }
+
+    pub fn is_level_discovered(&self, level_iid: &LevelIid) -> bool {
+        self.discovered_levels.contains(level_iid)
+    }
+
+    /// Mark the level as visited, return `true` if it is the first visit.
+    pub fn discover_level(&mut self, level_iid: &LevelIid) -> bool {
+        self.discovered_levels.insert(level_iid.clone())
+    }
+}
+
+/// Start each run with a fresh world.
+fn reset_world_state(mut world_state: ResMut<WorldState>) {
+    *world_state = WorldState::default();
+}
+
+fn record_level_discovery(
+    mut level_enter_event: MessageWriter<LevelEnterEvent>,
+    mut world_state: ResMut<WorldState>,
+    current_level: Res<CurrentLevel>,
+    player: Single<(Entity, &GlobalTransform), With<CharacterController>>,
+) {
+    let (player, player_transform) = player.into_inner();
+
+    if let Some(level_iid) = &current_level.iid {
+        level_enter_event.write(LevelEnterEvent {
+            player,
+            position: player_transform.translation().truncate(),
+            level: level_iid.clone(),
+            first_visit: world_state.discover_level(level_iid),
+        });
+    }
+}
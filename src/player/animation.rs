@@ -8,7 +8,7 @@ use bevy::{prelude::*, sprite::Anchor};
 use crate::{
     assets::collections::PlayerAssets,
     player::{
-        death::Dead,
+        death::{Dead, Spawning},
         physics::{CharacterController, Grounded},
     },
     utils::animation::{
@@ -65,6 +65,8 @@ pub enum PlayerAnimationState {
     Jump,
     Fall,
     Dead,
+    /// Appearing at the respawn point.
+    Spawn,
 }
 
 impl AnimationState for PlayerAnimationState {
@@ -72,6 +74,7 @@ impl AnimationState for PlayerAnimationState {
         match self {
             Self::Idle => 2,
             Self::Walk => 6,
+            Self::Dead | Self::Spawn => 6,
             _ => 1,
         }
     }
@@ -80,10 +83,21 @@ impl AnimationState for PlayerAnimationState {
         match self {
             Self::Idle => Some(Duration::from_millis(500)),
             Self::Walk => Some(Duration::from_millis(100)),
+            Self::Dead => Some(Duration::from_millis(100)),
+            // The whole row is played during the spawn time of 0.4 s
+            Self::Spawn => Some(Duration::from_millis(400 / 6)),
             _ => None,
         }
     }
 
+    fn is_looping(&self) -> bool {
+        !matches!(self, Self::Dead | Self::Spawn)
+    }
+
+    fn is_reversed(&self) -> bool {
+        matches!(self, Self::Spawn)
+    }
+
     fn get_start_frame(&self) -> usize {
         match self {
             PlayerAnimationState::Idle => 0,
@@ -91,6 +105,8 @@ impl AnimationState for PlayerAnimationState {
             PlayerAnimationState::Jump => 12,
             PlayerAnimationState::Fall => 18,
             PlayerAnimationState::Dead => 24,
+            // The player gets up by playing the death backwards
+            PlayerAnimationState::Spawn => 24,
         }
     }
 }
@@ -104,11 +120,12 @@ fn update_animation_movement(
             &mut PlayerAnimationState,
             Has<Grounded>,
             Has<Dead>,
+            Has<Spawning>,
         ),
         With<CharacterController>,
     >,
 ) {
-    for (linear_velocity, mut sprite, mut animation_state, is_grounded, is_dead) in
+    for (linear_velocity, mut sprite, mut animation_state, is_grounded, is_dead, is_spawning) in
         &mut player_query
     {
         if linear_velocity.x.abs() > 1.0 {
@@ -117,6 +134,8 @@ fn update_animation_movement(
 
         let new_state = if is_dead {
             PlayerAnimationState::Dead
+        } else if is_spawning {
+            PlayerAnimationState::Spawn
         } else if is_grounded {
             if linear_velocity.x.abs() < 1.0 {
                 PlayerAnimationState::Idle
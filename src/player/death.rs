@@ -2,33 +2,144 @@
 
 use bevy::prelude::*;
 
-use avian2d::math::Vector;
+use avian2d::{math::Vector, prelude::*};
+use bevy_ecs_ldtk::prelude::*;
 
-use sf_ui::prelude::Screen;
+use sf_ui::prelude::{Menu, Screen};
 
-use sf_events::{DeathEvent, RespawnEvent};
+use sf_events::{DeathCause, DeathEvent, RespawnEvent, RespawnRequestEvent, SpawnedEvent};
 
-use crate::player::{
-    movement::JumpAmount,
-    physics::{CharacterController, Grounded},
+use crate::{
+    PausableSystems, Pause,
+    camera::{MainCamera, clamp_to_level},
+    platformer::{
+        level::{CurrentLevel, level_world_bounds},
+        level_settings::{ActiveLevelSettings, LevelSettings},
+    },
+    player::{
+        movement::{JumpAmount, MovementSystems},
+        physics::{CharacterController, ExternalVelocity, Grounded},
+    },
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.insert_resource(RespawnPosition(Vec2::ZERO));
+    app.init_resource::<RespawnPoint>();
+    app.init_resource::<DeathSequenceSettings>();
+
+    // The player can't act during the death sequence
+    app.configure_sets(
+        Update,
+        PausableSystems.run_if(not(resource_exists::<DeathSequence>)),
+    );
+    app.configure_sets(
+        FixedUpdate,
+        PausableSystems.run_if(not(resource_exists::<DeathSequence>)),
+    );
 
     app.add_systems(
         Update,
         (
-            (update_dead, add_dead_on_death).chain(),
-            (spawn_body_on_death, respawn_player).chain(),
+            add_dead_on_death,
+            (
+                start_death_sequence
+                    .run_if(not(in_state(Menu::Death)).and(not(resource_exists::<DeathSequence>))),
+                update_death_sequence
+                    .run_if(resource_exists::<DeathSequence>.and(in_state(Pause(false)))),
+            )
+                .chain(),
+            (
+                spawn_body_on_death,
+                // The player is placed at the earliest the frame after the level selection
+                (
+                    place_pending_player.run_if(resource_exists::<PendingPlacement>),
+                    respawn_player,
+                )
+                    .chain()
+                    .in_set(RespawnSystems),
+            )
+                .chain(),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        FixedUpdate,
+        (
+            (update_spawning, update_invulnerability).before(MovementSystems),
+            // Sees the end of the invulnerability on the same frame
+            update_dead.after(update_invulnerability),
         )
+            .in_set(PausableSystems)
             .run_if(in_state(Screen::Gameplay)),
     );
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        (clear_pending_placement, clear_death_sequence),
+    );
+}
+
+/// Timing of the death sequence played before the death menu opens.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct DeathSequenceSettings {
+    /// Time given to the death animation.
+    pub animation_secs: f32,
+    /// Time the camera stays on the body after the animation.
+    pub hold_secs: f32,
+    /// Open the death menu as soon as the player dies.
+    pub skip: bool,
+}
+
+impl Default for DeathSequenceSettings {
+    fn default() -> Self {
+        Self {
+            animation_secs: 0.6,
+            hold_secs: 0.4,
+            skip: false,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+enum DeathStage {
+    Animation,
+    CameraHold,
+}
+
+/// Death sequence in progress, the player inputs are frozen while it exists.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct DeathSequence {
+    stage: DeathStage,
+    timer: Timer,
 }
 
-/// The position of the Respawn
+/// System set in which the level and the camera are moved to the respawn point.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct RespawnSystems;
+
+/// Player waiting for the level it goes to be spawned before being placed in it.
+///
+/// Used by the respawns and the entrances. The current level is not updated from the player
+/// position while it exists.
 #[derive(Resource, Reflect, Debug)]
-pub struct RespawnPosition(pub Vector);
+#[reflect(Resource)]
+pub struct PendingPlacement {
+    pub position: Vector,
+    pub level: Option<LevelIid>,
+    /// The player respawns at the [`RespawnPoint`] instead of only being moved.
+    pub is_respawn: bool,
+}
+
+/// Where and how the player respawns, set by the active checkpoint.
+#[derive(Resource, Reflect, Debug, Default)]
+pub struct RespawnPoint {
+    pub position: Vector,
+    pub facing_left: bool,
+    /// Jumps given back on respawn, all of them if `None`.
+    pub refill: Option<u32>,
+    /// Level owning the checkpoint.
+    pub level: Option<LevelIid>,
+}
 
 /// A marker component indicating that the player is dead.
 #[derive(Component, Reflect, Debug)]
@@ -36,6 +147,26 @@ pub struct RespawnPosition(pub Vector);
 #[component(storage = "SparseSet")]
 pub struct Dead;
 
+/// The player is appearing at the respawn point, its inputs are ignored.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct Spawning(Timer);
+
+/// Grace period after a respawn during which the player can't die.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct Invulnerable(Timer);
+
+const SPAWN_SECS: f32 = 0.4;
+const INVULNERABILITY_SECS: f32 = 1.0;
+/// Time the sprite stays shown, then hidden, while the player is invulnerable.
+const BLINK_SECS: f32 = 0.1;
+
+/// Body left by the player on death.
+///
+/// It is a child of the gameplay root, not of an LDtk level, so it stays when its level is despawned.
 #[derive(Component, Default, Reflect, Debug)]
 #[reflect(Component)]
 pub struct DeadBody;
@@ -43,10 +174,31 @@ pub struct DeadBody;
 /// Detect the last jump of the player and trigger "Dead" behavior
 fn update_dead(
     mut death_event: MessageWriter<DeathEvent>,
-    jump_amount: Single<&JumpAmount, (Added<Grounded>, With<CharacterController>)>,
+    mut invulnerability_end: RemovedComponents<Invulnerable>,
+    current_level: Res<CurrentLevel>,
+    player: Single<
+        (Entity, &Transform, &JumpAmount, Option<Ref<Grounded>>),
+        (
+            With<CharacterController>,
+            Without<Spawning>,
+            Without<Invulnerable>,
+        ),
+    >,
 ) {
-    if jump_amount.remaining == 0 {
-        death_event.write(DeathEvent);
+    // A landing during the spawn or the grace period counts once it ends
+    let is_invulnerability_over = invulnerability_end.read().count() > 0;
+
+    let (entity, transform, jump_amount, grounded) = *player;
+    let has_landed =
+        grounded.is_some_and(|grounded| grounded.is_added() || is_invulnerability_over);
+
+    if has_landed && jump_amount.remaining == 0 {
+        death_event.write(DeathEvent {
+            player: entity,
+            position: transform.translation.truncate(),
+            level: current_level.iid.clone(),
+            cause: DeathCause::OutOfJumps,
+        });
     }
 }
 
@@ -61,10 +213,60 @@ fn add_dead_on_death(
     }
 }
 
+fn start_death_sequence(
+    mut commands: Commands,
+    mut death_event: MessageReader<DeathEvent>,
+    settings: Res<DeathSequenceSettings>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if death_event.is_empty() {
+        return;
+    }
+    death_event.clear();
+
+    if settings.skip {
+        next_menu.set(Menu::Death);
+    } else {
+        commands.insert_resource(DeathSequence {
+            stage: DeathStage::Animation,
+            timer: Timer::from_seconds(settings.animation_secs, TimerMode::Once),
+        });
+    }
+}
+
+/// Let the death animation play, hold the camera on the body, then open the death menu.
+fn update_death_sequence(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<DeathSequenceSettings>,
+    mut sequence: ResMut<DeathSequence>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if !sequence.timer.tick(time.delta()).is_finished() {
+        return;
+    }
+
+    match sequence.stage {
+        DeathStage::Animation => {
+            sequence.stage = DeathStage::CameraHold;
+            sequence.timer = Timer::from_seconds(settings.hold_secs, TimerMode::Once);
+        }
+        DeathStage::CameraHold => {
+            commands.remove_resource::<DeathSequence>();
+            next_menu.set(Menu::Death);
+        }
+    }
+}
+
+fn clear_death_sequence(mut commands: Commands) {
+    commands.remove_resource::<DeathSequence>();
+}
+
 fn spawn_body_on_death(
     mut commands: Commands,
-    mut respawn_event: MessageReader<RespawnEvent>,
+    mut respawn_event: MessageReader<RespawnRequestEvent>,
     player: Single<(&Sprite, &Transform, &ChildOf), With<CharacterController>>,
+    level_settings: ActiveLevelSettings,
 ) {
     if respawn_event.is_empty() {
         return;
@@ -72,6 +274,13 @@ fn spawn_body_on_death(
 
     respawn_event.clear();
 
+    if level_settings
+        .get()
+        .is_some_and(|settings| !settings.persistent_corpses)
+    {
+        return;
+    }
+
     let (player_sprite, player_transform, player_childof) = *player;
 
     commands.spawn((
@@ -82,21 +291,158 @@ fn spawn_body_on_death(
     ));
 }
 
+/// Select the level of the respawn point and move the camera there in the same frame.
 fn respawn_player(
     mut commands: Commands,
-    mut respawn_event: MessageReader<RespawnEvent>,
-    respawn_position: Res<RespawnPosition>,
-    player: Single<(Entity, &mut Transform, &mut JumpAmount), With<CharacterController>>,
+    mut respawn_event: MessageReader<RespawnRequestEvent>,
+    respawn_point: Res<RespawnPoint>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut camera: Single<&mut Transform, With<MainCamera>>,
+    ldtk_project_handle: Single<&LdtkProjectHandle>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
 ) {
     if respawn_event.is_empty() {
         return;
     }
     respawn_event.clear();
 
-    let (entity, mut transform, mut jump_amount) = player.into_inner();
+    if let Some(level_iid) = respawn_point
+        .level
+        .as_ref()
+        .filter(|level_iid| current_level.iid.as_ref() != Some(*level_iid))
+    {
+        let ldtk_project = ldtk_project_assets
+            .get(*ldtk_project_handle)
+            .expect("Project should be loaded during gameplay.");
+        let level = ldtk_project
+            .get_raw_level_by_iid(&level_iid.to_string())
+            .expect("Respawn level should exist in LDtk project.");
+
+        // The level may not be spawned yet, the streaming loads it from this selection
+        *current_level = CurrentLevel {
+            iid: Some(level_iid.clone()),
+            bounds: level_world_bounds(level),
+        };
+        let position = clamp_to_level(respawn_point.position, current_level.bounds);
+        camera.translation = position.extend(camera.translation.z);
+    }
+
+    commands.insert_resource(PendingPlacement {
+        position: respawn_point.position,
+        level: respawn_point.level.clone(),
+        is_respawn: true,
+    });
+}
+
+/// Place the player once the level it goes to is spawned, and respawn it if it was dead.
+fn place_pending_player(
+    mut commands: Commands,
+    mut respawn_event: MessageWriter<RespawnEvent>,
+    pending_placement: Res<PendingPlacement>,
+    respawn_point: Res<RespawnPoint>,
+    levels: Query<&LevelIid, (With<LevelSettings>, With<Children>)>,
+    player: Single<
+        (
+            Entity,
+            &mut Transform,
+            &mut Sprite,
+            &mut JumpAmount,
+            &mut LinearVelocity,
+            &mut ExternalVelocity,
+        ),
+        With<CharacterController>,
+    >,
+) {
+    let is_level_spawned = pending_placement
+        .level
+        .as_ref()
+        .is_none_or(|pending_iid| levels.iter().any(|level_iid| level_iid == pending_iid));
+    if !is_level_spawned {
+        return;
+    }
+
+    let (
+        entity,
+        mut transform,
+        mut sprite,
+        mut jump_amount,
+        mut linear_velocity,
+        mut external_velocity,
+    ) = player.into_inner();
+
+    transform.translation = pending_placement.position.extend(transform.translation.z);
+    linear_velocity.0 = Vector::ZERO;
+    external_velocity.0 = 0.0;
+    commands.remove_resource::<PendingPlacement>();
+    // The body is frozen while the player waits for the level
+    commands.entity(entity).remove::<RigidBodyDisabled>();
+
+    if !pending_placement.is_respawn {
+        return;
+    }
+
+    sprite.flip_x = respawn_point.facing_left;
 
-    transform.translation = respawn_position.0.extend(transform.translation.z);
+    match respawn_point.refill {
+        Some(amount) => jump_amount.remaining = amount.min(jump_amount.max),
+        None => jump_amount.reset(),
+    }
+    commands
+        .entity(entity)
+        .remove::<Dead>()
+        .insert(Spawning(Timer::from_seconds(SPAWN_SECS, TimerMode::Once)));
+
+    respawn_event.write(RespawnEvent {
+        player: entity,
+        position: respawn_point.position,
+        level: respawn_point.level.clone(),
+    });
+}
+
+/// Give the control back once the spawn animation is over.
+fn update_spawning(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spawned_event: MessageWriter<SpawnedEvent>,
+    current_level: Res<CurrentLevel>,
+    player: Single<(Entity, &GlobalTransform, &mut Spawning), With<CharacterController>>,
+) {
+    let (entity, transform, mut spawning) = player.into_inner();
+    if !spawning.0.tick(time.delta()).is_finished() {
+        return;
+    }
+
+    commands
+        .entity(entity)
+        .remove::<Spawning>()
+        .insert(Invulnerable(Timer::from_seconds(
+            INVULNERABILITY_SECS,
+            TimerMode::Once,
+        )));
+    spawned_event.write(SpawnedEvent {
+        player: entity,
+        position: transform.translation().truncate(),
+        level: current_level.iid.clone(),
+    });
+}
+
+/// Blink the sprite of the player until the end of the grace period.
+fn update_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    player: Single<(Entity, &mut Invulnerable, &mut Sprite), With<CharacterController>>,
+) {
+    let (entity, mut invulnerable, mut sprite) = player.into_inner();
+
+    let is_finished = invulnerable.0.tick(time.delta()).is_finished();
+    let is_hidden = !is_finished && (invulnerable.0.elapsed_secs() / BLINK_SECS) as u32 % 2 == 1;
+    sprite.color.set_alpha(if is_hidden { 0.0 } else { 1.0 });
+
+    if is_finished {
+        commands.entity(entity).remove::<Invulnerable>();
+    }
+}
 
-    jump_amount.reset();
-    commands.entity(entity).remove::<Dead>();
+fn clear_pending_placement(mut commands: Commands) {
+    commands.remove_resource::<PendingPlacement>();
 }
@@ -3,23 +3,35 @@
 use bevy::platform::collections::HashMap;
 use bevy::{math::FloatPow, prelude::*};
 
+use sf_events::GenesChangedEvent;
 use sf_gene::{GeneDatabase, GeneDatabaseHandle, PlayerGenes};
 use sf_ui::prelude::Screen;
 
-use crate::player::movement::{JumpAmount, JumpImpulse, MovementSpeed};
+use crate::player::movement::{
+    JumpAmount, JumpImpulse, MovementModifierSystems, MovementSpeed, MovementSystems, SwimImpulse,
+};
 use crate::player::physics::{CharacterController, GravityController};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, player_genes_changed);
+    // On the fixed timestep, so the genes of a replay apply on the recorded frame
+    app.add_systems(
+        FixedUpdate,
+        player_genes_changed
+            .after(MovementModifierSystems::Inputs)
+            .before(MovementSystems),
+    );
     app.add_systems(OnEnter(Screen::Title), load_default_gene);
 }
 
 fn player_genes_changed(
+    mut genes_changed_event: MessageWriter<GenesChangedEvent>,
     player_genes: Res<PlayerGenes>,
     player: Single<
         (
+            Entity,
             &mut JumpAmount,
             &mut JumpImpulse,
+            &mut SwimImpulse,
             &mut MovementSpeed,
             &mut GravityController,
         ),
@@ -30,11 +42,21 @@ fn player_genes_changed(
         return;
     }
 
-    let (mut jump_amount, mut jump_impulse, mut movement_speed, mut controller_gravity) =
-        player.into_inner();
+    let (
+        entity,
+        mut jump_amount,
+        mut jump_impulse,
+        mut swim_impulse,
+        mut movement_speed,
+        mut controller_gravity,
+    ) = player.into_inner();
 
-    jump_amount.max = player_genes.total_jump_amount().max(0) as u32;
-    jump_amount.reset();
+    // Keep the jumps already spent, changing genes at a checkpoint is not a refill
+    let max_jumps = player_genes.total_jump_amount().max(0) as u32;
+    jump_amount.remaining = (jump_amount.remaining + max_jumps)
+        .saturating_sub(jump_amount.max)
+        .min(max_jumps);
+    jump_amount.max = max_jumps;
 
     movement_speed.0 = player_genes.total_movement_speed() * 8.0;
 
@@ -44,6 +66,14 @@ fn player_genes_changed(
 
     controller_gravity.jump_gravity = (2.0 * jump_height * 8.0) / jump_time.squared();
     jump_impulse.0 = controller_gravity.jump_gravity * jump_time;
+
+    // No gene changes the swim stroke yet, it follows the jump
+    swim_impulse.0 = jump_impulse.0 / 2.0;
+
+    genes_changed_event.write(GenesChangedEvent {
+        player: entity,
+        max_jumps,
+    });
 }
 
 fn load_default_gene(
@@ -5,20 +5,56 @@ use bevy::prelude::*;
 use avian2d::{math::*, prelude::*};
 use leafwing_input_manager::prelude::*;
 
-use sf_events::JumpEvent;
+use sf_events::{JumpEvent, LandEvent};
 
-use crate::player::death::Dead;
-use crate::player::physics::{CharacterController, Grounded};
-use crate::{Action, AppSystems, PausableSystems};
+use crate::platformer::level::CurrentLevel;
+use crate::player::death::{Dead, Spawning};
+use crate::player::physics::{CharacterController, ExternalVelocity, Grounded, Submerged};
+use crate::{Action, PausableSystems};
 
 pub(super) fn plugin(app: &mut App) {
+    // Fixed timestep like the physics, so a replay of the inputs is frame-exact
     app.add_systems(
-        Update,
+        FixedUpdate,
         (update_coyote_timer, movement, jump)
             .chain()
-            .in_set(AppSystems::RecordInput)
+            .in_set(MovementSystems)
             .in_set(PausableSystems),
     );
+    app.configure_sets(
+        FixedUpdate,
+        (
+            MovementModifierSystems::Inputs,
+            MovementModifierSystems::ForceZones,
+            MovementModifierSystems::Springs,
+            MovementModifierSystems::Pickups,
+            MovementModifierSystems::Entrances,
+        )
+            .chain()
+            .before(MovementSystems),
+    );
+    app.add_systems(Update, send_land_event.in_set(PausableSystems));
+}
+
+/// System set in which the player inputs are turned into movement, on the fixed timestep.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct MovementSystems;
+
+/// Systems changing the inputs, the velocity or the jumps of the player before [`MovementSystems`].
+///
+/// They run in this order, so each one sees what the previous ones did on the same frame.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MovementModifierSystems {
+    /// Live or replayed inputs and genes of the player.
+    Inputs,
+    /// Wind and conveyors accumulating their contribution.
+    ForceZones,
+    /// Springs replacing the velocity along their direction.
+    Springs,
+    /// Pickups giving jumps back.
+    Pickups,
+    /// Entrances moving the player and resetting its velocity.
+    Entrances,
 }
 
 /// The speed used for character movement.Oh
@@ -31,10 +67,15 @@ pub struct MovementSpeed(pub Scalar);
 #[reflect(Component)]
 pub struct JumpImpulse(pub Scalar);
 
+/// The speed given by a swim stroke.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct SwimImpulse(pub Scalar);
+
 /// The coyote timer of the Jump
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-struct CoyoteTimer(Timer);
+pub struct CoyoteTimer(Timer);
 
 impl Default for CoyoteTimer {
     fn default() -> Self {
@@ -50,6 +91,12 @@ impl CoyoteTimer {
     fn reset_timer(&mut self) {
         self.0.reset();
     }
+
+    /// Prevent any coyote jump until the character is grounded again.
+    pub fn consume(&mut self) {
+        let remaining = self.0.remaining();
+        self.0.tick(remaining);
+    }
 }
 
 /// The amount of jump that can do the player.
@@ -64,6 +111,11 @@ impl JumpAmount {
     pub fn reset(&mut self) {
         self.remaining = self.max;
     }
+
+    /// Give back `amount` jumps without going over the maximum.
+    pub fn refill(&mut self, amount: u32) {
+        self.remaining = (self.remaining + amount).min(self.max);
+    }
 }
 
 /// A bundle that contains components for character movement.
@@ -71,6 +123,7 @@ impl JumpAmount {
 pub struct MovementBundle {
     speed: MovementSpeed,
     jump_impulse: JumpImpulse,
+    swim_impulse: SwimImpulse,
     jump_amount: JumpAmount,
     coyote_timer: CoyoteTimer,
     input_map: InputMap<Action>,
@@ -81,6 +134,7 @@ impl MovementBundle {
         Self {
             speed: MovementSpeed(speed),
             jump_impulse: JumpImpulse(jump_impulse),
+            swim_impulse: SwimImpulse(jump_impulse / 2.0),
             jump_amount: JumpAmount::default(),
             coyote_timer: CoyoteTimer::default(),
             input_map: MovementBundle::default_input_map(),
@@ -103,6 +157,14 @@ impl MovementBundle {
         input_map.insert(Jump, KeyCode::Space);
         input_map.insert(Jump, GamepadButton::South);
 
+        // Interaction
+        input_map.insert(Interact, KeyCode::KeyE);
+        input_map.insert(Interact, GamepadButton::West);
+
+        // Menus
+        input_map.insert(Map, KeyCode::KeyM);
+        input_map.insert(Map, GamepadButton::Select);
+
         input_map
     }
 }
@@ -116,28 +178,63 @@ impl Default for MovementBundle {
 /// Responds to [`Action`] events and moves character controllers accordingly.
 fn movement(
     mut jump_event_writer: MessageWriter<JumpEvent>,
+    current_level: Res<CurrentLevel>,
     action_state: Single<&ActionState<Action>, With<CharacterController>>,
     controller: Single<
         (
+            Entity,
+            &Transform,
+            &JumpAmount,
             &MovementSpeed,
+            &SwimImpulse,
             &mut LinearVelocity,
+            &ExternalVelocity,
             &CoyoteTimer,
+            Option<&Submerged>,
             Has<Grounded>,
             Has<Dead>,
+            Has<Spawning>,
         ),
         With<CharacterController>,
     >,
 ) {
-    let (movement_speed, mut linear_velocity, coyote_timer, is_grounded, is_dead) =
-        controller.into_inner();
+    let (
+        entity,
+        transform,
+        jump_amount,
+        movement_speed,
+        swim_impulse,
+        mut linear_velocity,
+        external_velocity,
+        coyote_timer,
+        submerged,
+        is_grounded,
+        is_dead,
+        is_spawning,
+    ) = controller.into_inner();
 
-    if is_dead {
+    if is_dead || is_spawning {
         linear_velocity.x = 0.0;
         return;
     }
 
-    if action_state.just_pressed(&Action::Jump) && (is_grounded || coyote_timer.can_jump()) {
-        jump_event_writer.write(JumpEvent);
+    // Below the surface a jump is always a swim stroke, even from the floor of a pool.
+    // Jumping out of a liquid works like jumping from the ground.
+    let is_swimming = submerged.is_some_and(|submerged| !submerged.is_at_surface());
+    let can_jump = is_grounded || coyote_timer.can_jump() || submerged.is_some();
+
+    if action_state.just_pressed(&Action::Jump) {
+        if is_swimming {
+            // Swimming doesn't consume a jump
+            linear_velocity.y = swim_impulse.0;
+        } else if can_jump {
+            jump_event_writer.write(JumpEvent {
+                player: entity,
+                position: transform.translation.truncate(),
+                level: current_level.iid.clone(),
+                remaining_jumps: jump_amount.remaining,
+            });
+        }
     }
 
     let mut direction = 0;
@@ -149,7 +246,23 @@ fn movement(
         }
     }
 
-    linear_velocity.x = (direction as Scalar) * movement_speed.0;
+    let speed_factor = submerged.map_or(1.0, |submerged| submerged.speed_factor);
+    linear_velocity.x =
+        (direction as Scalar) * movement_speed.0 * speed_factor + external_velocity.0;
+}
+
+fn send_land_event(
+    mut land_event: MessageWriter<LandEvent>,
+    current_level: Res<CurrentLevel>,
+    player: Single<(Entity, &Transform, &JumpAmount), (Added<Grounded>, With<CharacterController>)>,
+) {
+    let (entity, transform, jump_amount) = *player;
+    land_event.write(LandEvent {
+        player: entity,
+        position: transform.translation.truncate(),
+        level: current_level.iid.clone(),
+        remaining_jumps: jump_amount.remaining,
+    });
 }
 
 /// Update the coyote timer every frame
@@ -166,11 +279,21 @@ fn update_coyote_timer(time: Res<Time>, players: Query<(&mut CoyoteTimer, Has<Gr
 /// Handle Jump Behavior
 fn jump(
     mut jump_event_reader: MessageReader<JumpEvent>,
-    player: Single<(&mut JumpAmount, &JumpImpulse, &mut LinearVelocity), With<CharacterController>>,
+    player: Single<
+        (
+            &mut JumpAmount,
+            &JumpImpulse,
+            &mut LinearVelocity,
+            &mut CoyoteTimer,
+        ),
+        With<CharacterController>,
+    >,
 ) {
-    let (mut jump_amount, jump_impulse, mut linear_velocity) = player.into_inner();
+    let (mut jump_amount, jump_impulse, mut linear_velocity, mut coyote_timer) =
+        player.into_inner();
     for _ in jump_event_reader.read() {
         linear_velocity.0.y = jump_impulse.0;
+        coyote_timer.consume();
 
         if jump_amount.remaining > 0 {
             jump_amount.remaining -= 1;
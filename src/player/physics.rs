@@ -12,6 +12,7 @@ pub(super) fn plugin(app: &mut App) {
         (
             update_grounded,
             apply_gravity,
+            apply_buoyancy,
             kinematic_controller_collisions,
         )
             .chain()
@@ -30,6 +31,39 @@ pub struct CharacterController;
 #[component(storage = "SparseSet")]
 pub struct Grounded;
 
+/// Horizontal velocity that doesn't come from the inputs (wind, conveyors, springs, etc.).
+///
+/// The movement overwrites the horizontal velocity every frame, so external
+/// contributions are kept here and added on top of the input-driven one.
+/// The vertical velocity is never overwritten, it receives them directly.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct ExternalVelocity(pub Scalar);
+
+/// A component indicating that an entity is inside a liquid.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct Submerged {
+    /// Vertical acceleration pushing the character up (negative to sink).
+    pub buoyancy: Scalar,
+    /// Fraction of the velocity lost per second.
+    pub drag: Scalar,
+    /// Factor applied to the horizontal movement speed.
+    pub speed_factor: Scalar,
+    /// Distance between the character and the surface of the liquid.
+    pub depth: Scalar,
+}
+
+impl Submerged {
+    /// Depth under which the character is considered at the surface of the liquid.
+    const SURFACE_DEPTH: Scalar = 4.0;
+
+    pub fn is_at_surface(&self) -> bool {
+        self.depth <= Self::SURFACE_DEPTH
+    }
+}
+
 /// The gravitational acceleration used for a character controller.
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
@@ -37,6 +71,8 @@ pub struct GravityController {
     pub jump_gravity: Scalar,
     pub fall_gravity: Scalar,
     pub terminal_velocity: Scalar,
+    /// Multiplier of the gravity set by the current level.
+    pub multiplier: Scalar,
 }
 
 impl Default for GravityController {
@@ -45,6 +81,7 @@ impl Default for GravityController {
             jump_gravity: 300.0,
             fall_gravity: 300.0,
             terminal_velocity: 200.0,
+            multiplier: 1.0,
         }
     }
 }
@@ -59,6 +96,7 @@ pub struct CharacterControllerBundle {
     collision_layer: CollisionLayers,
     ground_caster: ShapeCaster,
     gravity: GravityController,
+    external_velocity: ExternalVelocity,
 }
 
 impl CharacterControllerBundle {
@@ -79,6 +117,7 @@ impl CharacterControllerBundle {
                 .with_max_distance(1.0)
                 .with_max_hits(5),
             gravity: GravityController::default(),
+            external_velocity: ExternalVelocity::default(),
         }
     }
 
@@ -92,6 +131,7 @@ impl CharacterControllerBundle {
             jump_gravity,
             fall_gravity,
             terminal_velocity,
+            ..default()
         };
         self
     }
@@ -122,7 +162,10 @@ fn update_grounded(
 /// Applies he gravity to character controllers.
 fn apply_gravity(
     time: Res<Time>,
-    mut controllers: Query<(&GravityController, &mut LinearVelocity), Without<Grounded>>,
+    mut controllers: Query<
+        (&GravityController, &mut LinearVelocity),
+        (Without<Grounded>, Without<Submerged>),
+    >,
 ) {
     let delta_time = time.delta_secs();
     for (gravity, mut linear_velocity) in &mut controllers {
@@ -132,7 +175,7 @@ fn apply_gravity(
             gravity.fall_gravity
         };
 
-        linear_velocity.y -= gravity_force * delta_time;
+        linear_velocity.y -= gravity_force * gravity.multiplier * delta_time;
 
         if linear_velocity.y.abs() > gravity.terminal_velocity {
             linear_velocity.y = -gravity.terminal_velocity;
@@ -140,6 +183,18 @@ fn apply_gravity(
     }
 }
 
+/// Applies the buoyancy and the drag of the liquid instead of the gravity.
+fn apply_buoyancy(
+    time: Res<Time>,
+    mut controllers: Query<(&Submerged, &mut LinearVelocity), Without<Grounded>>,
+) {
+    let delta_time = time.delta_secs();
+    for (submerged, mut linear_velocity) in &mut controllers {
+        linear_velocity.y += submerged.buoyancy * delta_time;
+        linear_velocity.y *= (1.0 - submerged.drag * delta_time).max(0.0);
+    }
+}
+
 /// Kinematic bodies do not get pushed by collisions by default,
 /// so it needs to be done manually.
 ///
@@ -0,0 +1,287 @@
+//! Record the inputs of a run and play them back to reproduce physics bugs
+//!
+//! Each run is recorded in [`RECORD_PATH`]. Start the game with `--replay <path>` to play a
+//! recorded run instead of the live inputs.
+
+use std::{fs, path::Path};
+
+use bevy::prelude::*;
+
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use sf_events::RespawnRequestEvent;
+use sf_gene::PlayerGenes;
+use sf_ui::prelude::{Menu, Screen};
+
+use crate::{
+    Action, PausableSystems,
+    player::{movement::MovementModifierSystems, physics::CharacterController},
+    utils::rng::{RunSeed, seed_run_rng},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    let mode = match replay_path_argument() {
+        Some(path) => match load_replay(&path) {
+            Ok(replay) => ReplayMode::playback(replay),
+            Err(error) => {
+                warn!("Can't load the replay {path}: {error}");
+                ReplayMode::record()
+            }
+        },
+        None => ReplayMode::record(),
+    };
+
+    app.insert_resource(mode);
+    app.add_systems(OnEnter(Screen::Gameplay), start_run.before(seed_run_rng));
+    app.add_systems(OnExit(Screen::Gameplay), save_replay);
+    // Closing the game during a run does not leave the gameplay screen
+    app.add_systems(
+        Last,
+        save_replay.run_if(in_state(Screen::Gameplay).and(on_message::<AppExit>)),
+    );
+    app.add_systems(
+        FixedUpdate,
+        (record_inputs, play_inputs)
+            .in_set(MovementModifierSystems::Inputs)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        record_respawn_requests.run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// File in which the last run is recorded.
+const RECORD_PATH: &str = "replays/last_run.ron";
+
+/// Actions replayed by the movement on the fixed timestep, in the order of their bit.
+///
+/// The other actions only drive the menus and the camera.
+const RECORDED_ACTIONS: [Action; 4] = [Action::Left, Action::Right, Action::Jump, Action::Interact];
+
+/// Everything needed to play a run again.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Replay {
+    seed: u64,
+    genes: PlayerGenes,
+    /// Pressed actions of each fixed frame, run-length encoded as `(actions, frames)`.
+    inputs: Vec<(u8, u32)>,
+    /// Fixed frames after which the player asked to respawn from the death menu.
+    respawns: Vec<u32>,
+    /// Genes edited during the run, with the fixed frame on which they were applied.
+    gene_changes: Vec<(u32, PlayerGenes)>,
+}
+
+impl Replay {
+    fn push(&mut self, actions: u8) {
+        match self.inputs.last_mut() {
+            Some((last_actions, frames)) if *last_actions == actions => *frames += 1,
+            _ => self.inputs.push((actions, 1)),
+        }
+    }
+}
+
+#[derive(Resource, Debug)]
+enum ReplayMode {
+    Record {
+        replay: Replay,
+        /// Fixed frames recorded so far.
+        frame: u32,
+    },
+    Playback {
+        replay: Replay,
+        /// Index of the current run in the inputs.
+        input: usize,
+        /// Frames already played of the current run.
+        input_frame: u32,
+        /// Fixed frames played so far.
+        frame: u32,
+        /// Index of the next respawn to request.
+        respawn: usize,
+        /// Index of the next genes to apply.
+        gene_change: usize,
+    },
+}
+
+impl ReplayMode {
+    fn record() -> Self {
+        Self::Record {
+            replay: Replay::default(),
+            frame: 0,
+        }
+    }
+
+    fn playback(replay: Replay) -> Self {
+        Self::Playback {
+            replay,
+            input: 0,
+            input_frame: 0,
+            frame: 0,
+            respawn: 0,
+            gene_change: 0,
+        }
+    }
+}
+
+fn replay_path_argument() -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--replay");
+    args.next()?;
+    args.next()
+}
+
+fn load_replay(path: &str) -> Result<Replay, String> {
+    let content = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    ron::from_str(&content).map_err(|error| error.to_string())
+}
+
+/// Start the recording, or restore the seed and the genes of the replay.
+fn start_run(
+    mut commands: Commands,
+    mut mode: ResMut<ReplayMode>,
+    mut seed: ResMut<RunSeed>,
+    player_genes: Res<PlayerGenes>,
+) {
+    match mode.as_mut() {
+        ReplayMode::Record { replay, frame } => {
+            seed.0 = rand::random();
+            *replay = Replay {
+                seed: seed.0,
+                genes: player_genes.clone(),
+                ..default()
+            };
+            *frame = 0;
+        }
+        ReplayMode::Playback { replay, .. } => {
+            let replay = std::mem::take(replay);
+            seed.0 = replay.seed;
+            commands.insert_resource(replay.genes.clone());
+            *mode = ReplayMode::playback(replay);
+        }
+    }
+}
+
+fn save_replay(mode: Res<ReplayMode>) {
+    let ReplayMode::Record { replay, .. } = mode.as_ref() else {
+        return;
+    };
+
+    let result = ron::to_string(replay)
+        .map_err(|error| error.to_string())
+        .and_then(|content| {
+            if let Some(directory) = Path::new(RECORD_PATH).parent() {
+                fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+            }
+            fs::write(RECORD_PATH, content).map_err(|error| error.to_string())
+        });
+
+    match result {
+        Ok(()) => info!("Run recorded in {RECORD_PATH}"),
+        Err(error) => warn!("Can't save the replay: {error}"),
+    }
+}
+
+fn record_inputs(
+    mut mode: ResMut<ReplayMode>,
+    action_state: Single<&ActionState<Action>, With<CharacterController>>,
+    player_genes: Res<PlayerGenes>,
+) {
+    let ReplayMode::Record { replay, frame } = mode.as_mut() else {
+        return;
+    };
+
+    // Edited in a menu since the last frame, applied on this one
+    if player_genes.is_changed() {
+        replay.gene_changes.push((*frame, player_genes.clone()));
+    }
+
+    let actions = RECORDED_ACTIONS
+        .iter()
+        .enumerate()
+        .filter(|(_, action)| action_state.pressed(*action))
+        .fold(0, |actions, (bit, _)| actions | (1 << bit));
+    replay.push(actions);
+    *frame += 1;
+}
+
+/// Record the respawn button of the death menu, which is pressed between two fixed frames.
+fn record_respawn_requests(
+    mut mode: ResMut<ReplayMode>,
+    mut respawn_request: MessageReader<RespawnRequestEvent>,
+) {
+    let ReplayMode::Record { replay, frame } = mode.as_mut() else {
+        return;
+    };
+
+    for _ in respawn_request.read() {
+        replay.respawns.push(*frame);
+    }
+}
+
+/// Override the live inputs, genes and respawns with the ones of the replay.
+fn play_inputs(
+    mut mode: ResMut<ReplayMode>,
+    mut action_state: Single<&mut ActionState<Action>, With<CharacterController>>,
+    mut player_genes: ResMut<PlayerGenes>,
+    mut respawn_request: MessageWriter<RespawnRequestEvent>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    let ReplayMode::Playback {
+        replay,
+        input,
+        input_frame,
+        frame,
+        respawn,
+        gene_change,
+    } = mode.as_mut()
+    else {
+        return;
+    };
+
+    while let Some((_, genes)) = replay
+        .gene_changes
+        .get(*gene_change)
+        .filter(|(change_frame, _)| *change_frame <= *frame)
+    {
+        *player_genes = genes.clone();
+        *gene_change += 1;
+    }
+
+    *frame += 1;
+
+    // Requested on the last frame before the recorded one, the respawn happens in between
+    while replay
+        .respawns
+        .get(*respawn)
+        .is_some_and(|respawn_frame| *respawn_frame <= *frame)
+    {
+        respawn_request.write(RespawnRequestEvent);
+        next_menu.set(Menu::None);
+        *respawn += 1;
+    }
+
+    let Some((actions, frames)) = replay.inputs.get(*input).copied() else {
+        if *input == replay.inputs.len() {
+            info!("Replay finished");
+            *input += 1;
+        }
+        for action in &RECORDED_ACTIONS {
+            action_state.release(action);
+        }
+        return;
+    };
+
+    for (bit, action) in RECORDED_ACTIONS.iter().enumerate() {
+        if actions & (1 << bit) != 0 {
+            action_state.press(action);
+        } else {
+            action_state.release(action);
+        }
+    }
+
+    *input_frame += 1;
+    if *input_frame >= frames {
+        *input += 1;
+        *input_frame = 0;
+    }
+}
@@ -3,7 +3,6 @@
 use avian2d::prelude::{Physics, PhysicsTime};
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 
-use sf_events::DeathEvent;
 use sf_ui::prelude::{Menu, Screen};
 
 use crate::{Pause, platformer::level::spawn_level};
@@ -25,9 +24,12 @@ pub(super) fn plugin(app: &mut App) {
                     .and(not(in_state(Menu::None)))
                     .and(input_just_pressed(KeyCode::KeyP)),
             ),
-            open_death_menu.run_if(in_state(Screen::Gameplay).and(not(in_state(Menu::Death)))),
         ),
     );
+    app.add_systems(
+        OnEnter(Menu::Death),
+        spawn_death_overlay.run_if(in_state(Screen::Gameplay)),
+    );
     app.add_systems(OnExit(Screen::Gameplay), (close_menu, unpause));
     app.add_systems(
         OnEnter(Menu::None),
@@ -59,16 +61,7 @@ fn spawn_pause_overlay(mut commands: Commands) {
     ));
 }
 
-fn open_death_menu(
-    mut commands: Commands,
-    mut death_event: MessageReader<DeathEvent>,
-    mut next_menu: ResMut<NextState<Menu>>,
-) {
-    if death_event.is_empty() {
-        return;
-    }
-    death_event.clear();
-
+fn spawn_death_overlay(mut commands: Commands) {
     commands.spawn((
         Name::new("Death Overlay"),
         Node {
@@ -80,8 +73,6 @@ fn open_death_menu(
         BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
         DespawnOnExit(Menu::Death),
     ));
-
-    next_menu.set(Menu::Death);
 }
 
 fn open_pause_menu(mut next_menu: ResMut<NextState<Menu>>) {
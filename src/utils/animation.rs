@@ -23,6 +23,16 @@ pub trait AnimationState: Clone + Component {
     /// Return the duration of each frame of the animation
     /// None if no animation
     fn get_duration(&self) -> Option<Duration>;
+
+    /// Return false if the animation stops on its last frame
+    fn is_looping(&self) -> bool {
+        true
+    }
+
+    /// Return true if the frames are played from the last one to the first one
+    fn is_reversed(&self) -> bool {
+        false
+    }
 }
 
 /// Component that update the sprite to have an animation.
@@ -32,6 +42,7 @@ pub struct SpriteAnimation {
     timer: Option<Timer>,
     frame: usize,
     max_frame: usize,
+    looping: bool,
 }
 
 impl SpriteAnimation {
@@ -44,6 +55,7 @@ impl SpriteAnimation {
             timer,
             frame: 0,
             max_frame: state.get_frames(),
+            looping: state.is_looping(),
         }
     }
 
@@ -53,7 +65,11 @@ impl SpriteAnimation {
             timer.tick(delta);
 
             if timer.is_finished() {
-                self.frame = (self.frame + 1) % self.max_frame;
+                self.frame = if self.looping {
+                    (self.frame + 1) % self.max_frame
+                } else {
+                    (self.frame + 1).min(self.max_frame - 1)
+                };
             }
         }
     }
@@ -75,7 +91,12 @@ pub fn update_animation_atlas<T: AnimationState>(
             continue;
         };
 
-        let frame_index = animation.frame + state.get_start_frame();
+        let frame = if state.is_reversed() {
+            state.get_frames().saturating_sub(animation.frame + 1)
+        } else {
+            animation.frame
+        };
+        let frame_index = frame + state.get_start_frame();
         if atlas.index != frame_index {
             atlas.index = frame_index;
         }
@@ -0,0 +1,117 @@
+//! Generic code to resolve references between LDtk entities
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use bevy_ecs_ldtk::prelude::*;
+
+/// Component holding references to LDtk entities, resolved once they are spawned.
+///
+/// The referenced entities can live in a level that is not spawned yet, or that is
+/// despawned and spawned again by the streaming, so the component is kept and the
+/// references are resolved again each time one of their targets is spawned.
+pub trait UnresolvedEntityRefs: Component {
+    /// Return the LDtk entities referenced by this component
+    fn refs(&self) -> &[EntityIid];
+
+    /// Insert the resolved component, replacing the previous one if any
+    /// `resolved` contains the entities of the references that are not dangling
+    fn resolve(&self, commands: &mut Commands, entity: Entity, resolved: Vec<Entity>);
+}
+
+/// Resolve the references of `T` when new LDtk entities are spawned.
+pub fn resolve_entity_refs<T: UnresolvedEntityRefs>(
+    mut commands: Commands,
+    unresolved: Query<(Entity, &T)>,
+    new_unresolved: Query<(), Added<T>>,
+    new_ldtk_entities: Query<&EntityIid, Added<EntityIid>>,
+    ldtk_entities: Query<(Entity, &EntityIid)>,
+    ldtk_project_handle: Option<Single<&LdtkProjectHandle>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    // Only retry when something that could resolve a reference appeared
+    if new_unresolved.is_empty() && new_ldtk_entities.is_empty() {
+        return;
+    }
+
+    let spawned: HashMap<&EntityIid, Entity> = ldtk_entities
+        .iter()
+        .map(|(entity, iid)| (iid, entity))
+        .collect();
+    let new_iids: HashSet<&EntityIid> = new_ldtk_entities.iter().collect();
+
+    let ldtk_project = ldtk_project_handle.and_then(|handle| ldtk_project_assets.get(*handle));
+
+    for (entity, unresolved_ref) in &unresolved {
+        // Resolve the new components and the ones with a target that was (re)spawned
+        let is_new = new_unresolved.contains(entity);
+        if !is_new
+            && !unresolved_ref
+                .refs()
+                .iter()
+                .any(|entity_iid| new_iids.contains(entity_iid))
+        {
+            continue;
+        }
+
+        let mut resolved = Vec::new();
+        let mut is_pending = false;
+
+        for entity_iid in unresolved_ref.refs() {
+            if let Some(target) = spawned.get(entity_iid) {
+                resolved.push(*target);
+            } else if ldtk_project.is_some_and(|project| project_has_entity(project, entity_iid)) {
+                // The level of the target is not spawned yet
+                is_pending = true;
+            } else if is_new {
+                warn!(
+                    "Entity {entity} references the LDtk entity {} that doesn't exist",
+                    entity_iid.as_str()
+                );
+            }
+        }
+
+        if is_pending {
+            continue;
+        }
+
+        unresolved_ref.resolve(&mut commands, entity, resolved);
+    }
+}
+
+/// Whether any level of the project contains the given entity.
+fn project_has_entity(ldtk_project: &LdtkProject, entity_iid: &EntityIid) -> bool {
+    ldtk_project.iter_raw_levels().any(|level| {
+        level.layer_instances.iter().flatten().any(|layer| {
+            layer
+                .entity_instances
+                .iter()
+                .any(|entity_instance| entity_instance.iid == entity_iid.as_str())
+        })
+    })
+}
+
+/// World position of the center of an LDtk entity, read from the project so its level
+/// doesn't need to be spawned.
+pub fn ldtk_entity_position(ldtk_project: &LdtkProject, entity_iid: &EntityIid) -> Option<Vec2> {
+    ldtk_project.iter_raw_levels().find_map(|level| {
+        let entity_instance = level
+            .layer_instances
+            .iter()
+            .flatten()
+            .flat_map(|layer| &layer.entity_instances)
+            .find(|entity_instance| entity_instance.iid == entity_iid.as_str())?;
+
+        // LDtk positions are relative to the pivot, with y pointing down
+        let size = Vec2::new(entity_instance.width as f32, entity_instance.height as f32);
+        let center =
+            entity_instance.px.as_vec2() + (Vec2::splat(0.5) - entity_instance.pivot) * size;
+
+        Some(Vec2::new(
+            level.world_x as f32 + center.x,
+            -(level.world_y as f32 + center.y),
+        ))
+    })
+}
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 
 pub mod animation;
+pub mod entity_ref;
+pub mod rng;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(animation::plugin);
+    app.add_plugins((animation::plugin, rng::plugin));
 }
@@ -0,0 +1,33 @@
+//! Random number generator of the gameplay, seeded so a replay draws the same numbers
+
+use bevy::prelude::*;
+
+use rand::{SeedableRng, rngs::StdRng};
+
+use sf_ui::prelude::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(RunSeed(rand::random()));
+    app.init_resource::<RunRng>();
+    app.add_systems(OnEnter(Screen::Gameplay), seed_run_rng);
+}
+
+/// Seed of the random systems of the run, saved in the replays.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct RunSeed(pub u64);
+
+/// Generator every random gameplay system draws from, instead of `rand::rng()`.
+#[derive(Resource, Deref, DerefMut)]
+pub struct RunRng(StdRng);
+
+impl FromWorld for RunRng {
+    fn from_world(world: &mut World) -> Self {
+        Self(StdRng::seed_from_u64(world.resource::<RunSeed>().0))
+    }
+}
+
+/// Restart the generator from the seed of the run.
+pub fn seed_run_rng(mut rng: ResMut<RunRng>, seed: Res<RunSeed>) {
+    rng.0 = StdRng::seed_from_u64(seed.0);
+}